@@ -1,16 +1,19 @@
 use std::collections::VecDeque;
-use crossterm::style::Color;
+use crossterm::style::Attributes;
 use textwrap::wrap;
 use crate::frame::Frame;
+use crate::theme::Role;
 
 struct Message {
     lines: Vec<String>,
+    role: Role,
+    attrs: Attributes,
 }
 
 impl Message {
-    fn new(text: &str, max_width: usize) -> Self {
+    fn new(text: &str, max_width: usize, role: Role, attrs: Attributes) -> Self {
         let wrapped_lines = ActivityLog::wrap_text(text, max_width);
-        Message { lines: wrapped_lines }
+        Message { lines: wrapped_lines, role, attrs }
     }
 }
 
@@ -37,7 +40,13 @@ impl ActivityLog {
 
     /// Adds a new entry to the activity log.
     pub fn add_entry(&mut self, text: &str) {
-        let message = Message::new(text, self.width as usize);
+        self.add_styled_entry(text, Role::LogInfo, Attributes::none());
+    }
+
+    /// Adds a new entry drawn in the theme color of `role` with the given text attributes, e.g.
+    /// a bold warning for critical events.
+    pub fn add_styled_entry(&mut self, text: &str, role: Role, attrs: Attributes) {
+        let message = Message::new(text, self.width as usize, role, attrs);
 
         self.entries.push_back(message);
 
@@ -51,7 +60,7 @@ impl ActivityLog {
         // Collect all lines from all messages
         for message in &self.entries {
             for line in &message.lines {
-                all_lines.push(line.clone());
+                all_lines.push((line.clone(), message.role, message.attrs));
             }
         }
 
@@ -65,14 +74,15 @@ impl ActivityLog {
         let lines_to_draw = &all_lines[start..];
 
         // Draw each line sequentially
-        for (i, line) in lines_to_draw.iter().enumerate() {
+        for (i, (line, role, attrs)) in lines_to_draw.iter().enumerate() {
             let current_y = self.y + i as u16;
-            frame.draw_text(self.x, current_y, line, Some(Color::White), None);
+            frame.draw_text(self.x, current_y, line, frame.color(*role), None, *attrs);
         }
     }
 
 
-    /// Wraps text into lines not exceeding the specified width.
+    /// Wraps text into lines not exceeding the specified width, measured in terminal columns so
+    /// wide characters count as two.
     fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
         wrap(text, max_width)
             .iter()
@@ -91,7 +101,8 @@ impl ActivityLog {
         let mut rewrapped_entries = VecDeque::new();
 
         for message in &self.entries {
-            let rewrapped_message = Message::new(&message.lines.join(" "), width as usize);
+            let rewrapped_message =
+                Message::new(&message.lines.join(" "), width as usize, message.role, message.attrs);
             rewrapped_entries.push_back(rewrapped_message);
         }
 
@@ -109,10 +120,6 @@ impl ActivityLog {
             }
         }
     }
-
-    pub fn clear(&mut self) {
-        self.entries.clear();
-    }
 }
 
 #[cfg(test)]
@@ -140,4 +147,18 @@ mod tests {
         // Drawing at (10, 10): attack.
         // Since height is 5, only the last 5 lines should be drawn.
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_wide_characters_wrap_by_display_width() {
+        let mut log = ActivityLog::new(0, 0, 6, 5);
+        log.add_entry("洞窟の奥へ");
+
+        let mut frame = Frame::new(0, 0, 10, 5);
+        log.draw(&mut frame);
+
+        // Five wide characters need ten columns, so they wrap after three.
+        let first_row: String = frame.buffer[..10].iter().map(|cell| cell.ch).collect();
+        assert_eq!(first_row.replace(crate::frame::CONTINUATION, ""), "洞窟の    ");
+        assert_eq!(frame.buffer[frame.width as usize].ch, '奥');
+    }
+}
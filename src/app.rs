@@ -0,0 +1,490 @@
+use crate::frame::{Frame, Layer};
+use crate::game::Game;
+use crate::keymap::Command;
+use crate::layout::Rect;
+use crate::mapgen::LevelKind;
+use crate::options::Options;
+use crate::save::SaveData;
+use crate::theme::Role;
+use crate::widget::dialog::Dialog;
+use crate::widget::list::List;
+use crate::widget::render;
+use crossterm::event::{Event, KeyCode};
+use crossterm::style::{Attribute, Attributes};
+use std::time::Duration;
+
+/// The README, whose first code block holds the banner drawn on the title screen.
+const README: &str = include_str!("../README.md");
+
+/// Drawn instead of the banner when the terminal is too small for it.
+const TITLE: &str = "Cave Crawler";
+
+/// Widest the title screen menu gets.
+const MENU_WIDTH: u16 = 20;
+
+/// Preferred width of the game over summary, which has more buttons than the other dialogs.
+const SUMMARY_WIDTH: u16 = 56;
+
+/// The screen being shown. Each one handles its own input and drawing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Screen {
+    Title,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+/// An entry of a menu on one of the screens around the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MenuItem {
+    NewGame,
+    Continue,
+    /// Starts over with the seed of the run that just ended.
+    SameSeed,
+    NewSeed,
+    Resume,
+    Save,
+    TitleScreen,
+    Quit,
+}
+
+impl MenuItem {
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::NewGame => "New game",
+            MenuItem::Continue => "Continue",
+            MenuItem::SameSeed => "Same seed",
+            MenuItem::NewSeed => "New seed",
+            MenuItem::Resume => "Resume",
+            MenuItem::Save => "Save",
+            MenuItem::TitleScreen => "Title",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// App owns the game and the screens around it, and decides which one gets input and is drawn.
+pub struct App {
+    pub game: Game,
+    pub screen: Screen,
+    /// The selected entry of the current screen's menu.
+    selected: usize,
+    /// A message for the current screen, such as whether saving worked.
+    notice: Option<String>,
+    pub request_exit: bool,
+    options: Options,
+    width: u16,
+    height: u16,
+}
+
+impl App {
+    pub fn new(width: u16, height: u16, options: Options) -> Self {
+        let seed = options.seed.unwrap_or_else(rand::random);
+        // Nobody is there to pick from the title menu of a headless run, so it starts playing
+        let screen = if options.headless {
+            Screen::Playing
+        } else {
+            Screen::Title
+        };
+
+        App {
+            game: new_game(&options, width, height, seed, options.level.clone()),
+            screen,
+            selected: 0,
+            notice: None,
+            request_exit: false,
+            options,
+            width,
+            height,
+        }
+    }
+
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+        self.game.resize(width, height);
+    }
+
+    /// Switches to another screen with the first entry of its menu selected.
+    fn show(&mut self, screen: Screen) {
+        self.screen = screen;
+        self.selected = 0;
+        self.notice = None;
+    }
+
+    fn menu(&self) -> Vec<MenuItem> {
+        match self.screen {
+            Screen::Title if self.options.save_path.exists() => {
+                vec![MenuItem::NewGame, MenuItem::Continue, MenuItem::Quit]
+            }
+            Screen::Title => vec![MenuItem::NewGame, MenuItem::Quit],
+            Screen::Playing => Vec::new(),
+            Screen::Paused => vec![MenuItem::Resume, MenuItem::Save, MenuItem::Quit],
+            Screen::GameOver => vec![
+                MenuItem::SameSeed,
+                MenuItem::NewSeed,
+                MenuItem::TitleScreen,
+                MenuItem::Quit,
+            ],
+        }
+    }
+
+    /// Advances by one simulation tick. While playing the events go to the game; on every other
+    /// screen they drive its menu and the game stands still.
+    pub fn update(&mut self, events: &[Event], dt: Duration) {
+        if self.screen != Screen::Playing {
+            for event in events {
+                self.handle_menu_event(event);
+            }
+
+            return;
+        }
+
+        self.game.update(events, dt);
+
+        if self.game.is_over() {
+            self.show(Screen::GameOver);
+        } else if self.game.request_pause {
+            self.game.request_pause = false;
+            self.show(Screen::Paused);
+        }
+    }
+
+    fn handle_menu_event(&mut self, event: &Event) {
+        let Event::Key(key_event) = event else {
+            return;
+        };
+        let items = self.menu().len();
+
+        if key_event.code == KeyCode::Enter {
+            if let Some(&item) = self.menu().get(self.selected) {
+                self.choose(item);
+            }
+
+            return;
+        }
+
+        match self.game.keymap.command(key_event.code) {
+            Some(Command::MoveUp | Command::MoveLeft) if items > 0 => {
+                self.selected = (self.selected + items - 1) % items;
+            }
+            Some(Command::MoveDown | Command::MoveRight) if items > 0 => {
+                self.selected = (self.selected + 1) % items;
+            }
+            Some(Command::Pause) => match self.screen {
+                Screen::Title => self.request_exit = true,
+                Screen::Paused => self.show(Screen::Playing),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn choose(&mut self, item: MenuItem) {
+        match item {
+            MenuItem::NewGame => {
+                let seed = self.options.seed.unwrap_or_else(rand::random);
+                self.start(seed, self.options.level.clone());
+            }
+            MenuItem::SameSeed => self.start(self.game.seed, self.game.level_kind.clone()),
+            MenuItem::NewSeed => self.start(rand::random(), self.game.level_kind.clone()),
+            MenuItem::Continue => match SaveData::load(&self.options.save_path) {
+                Ok(save) => {
+                    self.game = new_game(
+                        &self.options,
+                        self.width,
+                        self.height,
+                        save.seed,
+                        save.level.clone(),
+                    );
+                    self.game.restore(&save);
+                    self.show(Screen::Playing);
+                }
+                Err(err) => self.notice = Some(format!("Could not continue: {}", err)),
+            },
+            MenuItem::Resume => self.show(Screen::Playing),
+            MenuItem::Save => {
+                let path = &self.options.save_path;
+                self.notice = Some(match self.game.save_data().write(path) {
+                    Ok(()) => format!("Saved to {}.", path.display()),
+                    Err(err) => format!("Could not save: {}", err),
+                });
+            }
+            MenuItem::TitleScreen => self.show(Screen::Title),
+            MenuItem::Quit => self.request_exit = true,
+        }
+    }
+
+    /// Drops the current game, and with it everything from the previous run, for a new one.
+    fn start(&mut self, seed: u64, kind: LevelKind) {
+        self.game = new_game(&self.options, self.width, self.height, seed, kind);
+        self.show(Screen::Playing);
+    }
+
+    /// How the run went, for the game over screen.
+    fn summary(&self) -> String {
+        let game = &self.game;
+        format!(
+            "{}\n\nYou survived {} {} and killed {} {}.\n\nSeed: {}",
+            game.cause_of_death(),
+            game.turns,
+            if game.turns == 1 { "turn" } else { "turns" },
+            game.kills,
+            if game.kills == 1 { "enemy" } else { "enemies" },
+            game.seed
+        )
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        if self.screen == Screen::Title {
+            self.draw_title(frame);
+            return;
+        }
+
+        frame.set_camera(self.game.camera.x, self.game.camera.y);
+        frame.set_zoom(self.game.camera.zoom);
+        self.game.draw(frame);
+        self.game.draw_ui(frame);
+
+        let labels: Vec<&str> = self.menu().iter().map(|item| item.label()).collect();
+        let mut dialog = match self.screen {
+            Screen::Paused => Dialog::new("Paused", "", &labels),
+            Screen::GameOver => {
+                let mut dialog = Dialog::new("You died", &self.summary(), &labels);
+                dialog.width = SUMMARY_WIDTH;
+                dialog
+            }
+            _ => return,
+        };
+        if let Some(notice) = &self.notice {
+            dialog.message = notice.clone();
+        }
+        dialog.selected = self.selected;
+
+        frame.set_layer(Layer::Ui);
+        render(&dialog, Rect::new(0, 0, self.width, self.height), frame);
+    }
+
+    /// Draws the banner with the menu beneath it, both centered.
+    fn draw_title(&self, frame: &mut Frame) {
+        frame.set_zoom(1);
+        frame.clear();
+        frame.set_layer(Layer::Ui);
+
+        let items = self.menu();
+        let banner = banner();
+        let banner_width = banner
+            .iter()
+            .map(|line| frame.text_width(line))
+            .max()
+            .unwrap_or(0);
+        // The menu, a blank line above it and the notice below it
+        let menu_height = items.len() as u16 + 3;
+
+        let title: Vec<&str> =
+            if banner_width <= self.width && banner.len() as u16 + menu_height <= self.height {
+                banner
+            } else {
+                vec![TITLE]
+            };
+        let title_width = title
+            .iter()
+            .map(|line| frame.text_width(line))
+            .max()
+            .unwrap_or(0);
+
+        let top = self.height.saturating_sub(title.len() as u16 + menu_height) / 2;
+        let left = self.width.saturating_sub(title_width) / 2;
+        let accent = frame.color(Role::UiAccent);
+
+        for (row, line) in title.iter().enumerate() {
+            frame.draw_text(
+                left,
+                top + row as u16,
+                line,
+                accent,
+                None,
+                Attributes::from(Attribute::Bold),
+            );
+        }
+
+        let mut list = List::new(items.iter().map(|item| item.label().to_string()).collect());
+        list.selected = Some(self.selected);
+
+        let width = MENU_WIDTH.min(self.width);
+        let menu_top = top + title.len() as u16 + 1;
+        render(
+            &list,
+            Rect::new(
+                self.width.saturating_sub(width) / 2,
+                menu_top,
+                width,
+                items.len() as u16,
+            ),
+            frame,
+        );
+
+        if let Some(notice) = &self.notice {
+            let x = self.width.saturating_sub(frame.text_width(notice)) / 2;
+            frame.draw_text(
+                x,
+                menu_top + items.len() as u16 + 1,
+                notice,
+                frame.color(Role::LogWarning),
+                None,
+                Attributes::none(),
+            );
+        }
+    }
+}
+
+/// A fresh game set up the way the command line asked for.
+fn new_game(options: &Options, width: u16, height: u16, seed: u64, kind: LevelKind) -> Game {
+    let mut game = Game::with_seed(width, height, seed, kind);
+    game.camera.mode = options.camera_mode;
+    game.camera.clamp = options.clamp_camera;
+    game.mode = options.mode;
+    game.keymap = options.keymap.clone();
+    game
+}
+
+/// The lines of the README's banner, without trailing spaces.
+fn banner() -> Vec<&'static str> {
+    let mut lines: Vec<&str> = README
+        .lines()
+        .skip_while(|line| !line.starts_with("```"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("```"))
+        .map(str::trim_end)
+        .collect();
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::HeadlessBackend;
+    use crate::backend::Backend;
+    use crossterm::event::KeyEvent;
+
+    const TICK: Duration = Duration::from_millis(33);
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::from(code))
+    }
+
+    fn app(save: &str) -> App {
+        let save_path =
+            std::env::temp_dir().join(format!("cave-diver-{}-{}.save", save, std::process::id()));
+        let _ = std::fs::remove_file(&save_path);
+        let options = Options::parse(["--save".to_string(), save_path.display().to_string()]);
+
+        App::new(80, 24, options.unwrap())
+    }
+
+    fn draw(app: &mut App) -> String {
+        let mut backend = HeadlessBackend::new(app.width, app.height);
+        let mut frame = Frame::new(0, 0, app.width, app.height);
+        app.draw(&mut frame);
+        backend.present(&frame).unwrap();
+        backend.to_text()
+    }
+
+    #[test]
+    fn test_title_leads_to_game_and_pause_menu() {
+        let mut app = app("pause");
+        assert!(draw(&mut app).contains(TITLE));
+
+        app.update(&[key(KeyCode::Enter)], TICK);
+        assert_eq!(app.screen, Screen::Playing);
+
+        app.update(&[key(KeyCode::Esc)], TICK);
+        assert_eq!(app.screen, Screen::Paused);
+        assert!(draw(&mut app).contains("[ Resume ]"));
+
+        app.update(&[key(KeyCode::Esc)], TICK);
+        assert_eq!(app.screen, Screen::Playing);
+    }
+
+    #[test]
+    fn test_title_shows_the_banner_when_it_fits() {
+        let mut app = app("banner");
+        let banner = banner();
+        assert!(draw(&mut app).contains(TITLE));
+
+        let width = banner.iter().map(|line| line.chars().count()).max().unwrap();
+        app.resize(width as u16, 24);
+        let screen = draw(&mut app);
+        assert!(!screen.contains(TITLE));
+        assert!(banner.iter().all(|line| screen.contains(line.trim_start())));
+    }
+
+    #[test]
+    fn test_headless_run_dumps_the_game() {
+        let options = Options::parse(["--headless".to_string()]).unwrap();
+        let mut app = App::new(80, 24, options);
+
+        let screen = draw(&mut app);
+        assert!(!screen.contains(TITLE));
+        assert!(screen.contains("Depth: 1"));
+    }
+
+    #[test]
+    fn test_saved_game_can_be_continued() {
+        let mut app = app("continue");
+        app.update(&[key(KeyCode::Enter)], TICK);
+        app.update(&[key(KeyCode::Right), key(KeyCode::Right)], TICK);
+        let position = (app.game.player.x, app.game.player.y);
+
+        // Pause, then pick Save
+        app.update(&[key(KeyCode::Esc)], TICK);
+        app.update(&[key(KeyCode::Down), key(KeyCode::Enter)], TICK);
+        assert!(draw(&mut app).contains("Saved to"));
+
+        // Quit to a fresh app and pick Continue from the title screen
+        let mut app = App::new(80, 24, app.options.clone());
+        app.update(&[key(KeyCode::Down), key(KeyCode::Enter)], TICK);
+        let _ = std::fs::remove_file(&app.options.save_path);
+
+        assert_eq!(app.screen, Screen::Playing);
+        assert_eq!((app.game.player.x, app.game.player.y), position);
+    }
+
+    #[test]
+    fn test_death_shows_game_over() {
+        let mut app = app("death");
+        app.update(&[key(KeyCode::Enter)], TICK);
+
+        app.game.player.health.set_current(0);
+        app.update(&[], TICK);
+
+        assert_eq!(app.screen, Screen::GameOver);
+        let screen = draw(&mut app);
+        assert!(screen.contains("You died"));
+        assert!(screen.contains("You survived 0 turns and killed 0 enemies."));
+    }
+
+    #[test]
+    fn test_restart_with_same_seed_starts_a_fresh_run() {
+        let mut app = app("restart");
+        app.update(&[key(KeyCode::Enter)], TICK);
+        let seed = app.game.seed;
+        let start = (app.game.player.x, app.game.player.y);
+
+        app.update(&[key(KeyCode::Right)], TICK);
+        app.game.player.health.set_current(0);
+        app.update(&[], TICK);
+        app.update(&[key(KeyCode::Enter)], TICK);
+
+        assert_eq!(app.screen, Screen::Playing);
+        assert_eq!(app.game.seed, seed);
+        assert_eq!(app.game.turns, 0);
+        assert!(app.game.player.health.is_alive());
+        assert_eq!((app.game.player.x, app.game.player.y), start);
+    }
+}
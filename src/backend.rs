@@ -0,0 +1,19 @@
+pub(crate) mod headless;
+pub(crate) mod terminal;
+
+use crate::frame::Frame;
+use crossterm::event::Event;
+use std::io::Result;
+use std::time::Duration;
+
+/// Backend is the surface a `Frame` is presented through and the source of input events.
+pub trait Backend {
+    /// Returns the size of the drawable area as `(width, height)`.
+    fn size(&self) -> Result<(u16, u16)>;
+
+    /// Waits up to `timeout` for the next input event.
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>>;
+
+    /// Presents a fully drawn frame.
+    fn present(&mut self, frame: &Frame) -> Result<()>;
+}
@@ -0,0 +1,185 @@
+use crate::backend::Backend;
+use crate::frame::{Frame, CONTINUATION};
+use crate::renderer::queue_attributes;
+use crossterm::event::Event;
+use crossterm::queue;
+use crossterm::style::{
+    Attribute, Attributes, Color, Print, ResetColor, SetAttribute, SetBackgroundColor,
+    SetForegroundColor,
+};
+use std::io::Result;
+use std::time::Duration;
+
+/// HeadlessBackend keeps presented frames in memory instead of drawing them, so screens can be
+/// inspected in tests or dumped when no terminal is attached.
+pub struct HeadlessBackend {
+    width: u16,
+    height: u16,
+    last_frame: Option<Frame>,
+    pub frames_presented: usize,
+}
+
+impl HeadlessBackend {
+    pub fn new(width: u16, height: u16) -> Self {
+        HeadlessBackend {
+            width,
+            height,
+            last_frame: None,
+            frames_presented: 0,
+        }
+    }
+
+    /// Returns the last presented frame as plain text, one line per row with trailing spaces
+    /// trimmed.
+    pub fn to_text(&self) -> String {
+        match &self.last_frame {
+            Some(frame) => frame_to_text(frame),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the last presented frame as text with ANSI color and attribute escapes.
+    pub fn to_ansi(&self) -> String {
+        match &self.last_frame {
+            Some(frame) => frame_to_ansi(frame),
+            None => String::new(),
+        }
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok((self.width, self.height))
+    }
+
+    /// Nobody types into a headless run, so this only waits out `timeout` like an idle
+    /// terminal would.
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        std::thread::sleep(timeout);
+
+        Ok(None)
+    }
+
+    fn present(&mut self, frame: &Frame) -> Result<()> {
+        self.last_frame = Some(frame.clone());
+        self.frames_presented += 1;
+
+        Ok(())
+    }
+}
+
+/// Dumps a frame as plain text, one line per row with trailing spaces trimmed.
+pub fn frame_to_text(frame: &Frame) -> String {
+    let mut lines = Vec::with_capacity(frame.height as usize);
+
+    for row in frame.buffer.chunks(frame.width.max(1) as usize) {
+        let line: String = row
+            .iter()
+            .map(|cell| cell.ch)
+            .filter(|ch| *ch != CONTINUATION)
+            .collect();
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.join("\n")
+}
+
+/// Dumps a frame as text with ANSI color and attribute escapes, resetting them at the end of
+/// every row.
+pub fn frame_to_ansi(frame: &Frame) -> String {
+    let mut out: Vec<u8> = Vec::new();
+
+    for row in frame.buffer.chunks(frame.width.max(1) as usize) {
+        let mut fg: Option<Color> = None;
+        let mut bg: Option<Color> = None;
+        let mut attrs = Attributes::none();
+
+        for cell in row {
+            if queue_attributes(&mut out, attrs, cell.attrs).unwrap() {
+                fg = None;
+                bg = None;
+            }
+            attrs = cell.attrs;
+
+            if cell.fg != fg {
+                queue!(out, SetForegroundColor(cell.fg.unwrap_or(Color::Reset))).unwrap();
+                fg = cell.fg;
+            }
+            if cell.bg != bg {
+                queue!(out, SetBackgroundColor(cell.bg.unwrap_or(Color::Reset))).unwrap();
+                bg = cell.bg;
+            }
+
+            if cell.ch != CONTINUATION {
+                queue!(out, Print(cell.ch)).unwrap();
+            }
+        }
+
+        if !attrs.is_empty() {
+            queue!(out, SetAttribute(Attribute::Reset)).unwrap();
+        } else if fg.is_some() || bg.is_some() {
+            queue!(out, ResetColor).unwrap();
+        }
+        out.push(b'\n');
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::mapgen::LevelKind;
+
+    fn draw_game(game: &mut Game, backend: &mut HeadlessBackend) {
+        let (width, height) = backend.size().unwrap();
+        game.update(&[], Duration::ZERO);
+
+        let mut frame = Frame::new(game.camera.x, game.camera.y, width, height);
+        frame.set_zoom(game.camera.zoom);
+        game.draw(&mut frame);
+        game.draw_ui(&mut frame);
+
+        backend.present(&frame).unwrap();
+    }
+
+    #[test]
+    fn test_text_dump_trims_rows() {
+        let mut backend = HeadlessBackend::new(8, 2);
+        let mut frame = Frame::new(0, 0, 8, 2);
+        frame.draw_text(1, 1, "hi", None, None, Attributes::none());
+
+        backend.present(&frame).unwrap();
+
+        assert_eq!(backend.to_text(), "\n hi");
+    }
+
+    #[test]
+    fn test_ansi_dump_contains_colors() {
+        let mut backend = HeadlessBackend::new(4, 1);
+        let mut frame = Frame::new(0, 0, 4, 1);
+        frame.draw_text(0, 0, "ok", Some(Color::Red), None, Attributes::none());
+
+        backend.present(&frame).unwrap();
+
+        assert_eq!(backend.to_ansi(), "\x1b[38;5;9mok\x1b[39m  \n");
+    }
+
+    #[test]
+    fn test_game_screen_snapshot() {
+        let mut backend = HeadlessBackend::new(60, 21);
+        let mut game = Game::with_seed(60, 21, 0, LevelKind::Demo);
+
+        draw_game(&mut game, &mut backend);
+
+        let text = backend.to_text();
+        let lines: Vec<&str> = text.split('\n').collect();
+
+        assert_eq!(lines.len(), 21);
+        // The player is centered in the map viewport above the UI separator.
+        assert_eq!(lines[7].chars().nth(30), Some('@'));
+        assert!(lines[14].starts_with("——————"));
+        assert!(lines[15].contains("Health: 100/100"));
+    }
+}
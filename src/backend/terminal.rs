@@ -0,0 +1,67 @@
+use crate::backend::Backend;
+use crate::frame::Frame;
+use crate::renderer::Renderer;
+use crate::theme::ColorDepth;
+use crossterm::cursor::{Hide, Show};
+use crossterm::event::{poll, read, Event};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, size, BeginSynchronizedUpdate, Clear, ClearType,
+    EndSynchronizedUpdate, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+};
+use crossterm::{execute, queue};
+use std::io::{stdout, Result, Stdout};
+use std::time::Duration;
+
+/// TerminalBackend draws to the real terminal through crossterm.
+pub struct TerminalBackend {
+    stdout: Stdout,
+    renderer: Renderer,
+}
+
+impl TerminalBackend {
+    /// Switches the terminal into raw mode on the alternate screen. Colors are downgraded to
+    /// `color_depth` when presented.
+    pub fn new(color_depth: ColorDepth) -> Result<Self> {
+        let mut stdout = stdout();
+
+        enable_raw_mode()?;
+        execute!(
+            stdout,
+            EnterAlternateScreen,
+            Hide,
+            Clear(ClearType::Purge),
+            SetTitle("Cave Diver Terminal")
+        )?;
+
+        Ok(TerminalBackend {
+            stdout,
+            renderer: Renderer::new(color_depth),
+        })
+    }
+
+    /// Returns the terminal to its normal state.
+    pub fn restore(&mut self) -> Result<()> {
+        execute!(self.stdout, LeaveAlternateScreen, Show)?;
+        disable_raw_mode()
+    }
+}
+
+impl Backend for TerminalBackend {
+    fn size(&self) -> Result<(u16, u16)> {
+        size()
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        if poll(timeout)? {
+            return read().map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn present(&mut self, frame: &Frame) -> Result<()> {
+        queue!(self.stdout, BeginSynchronizedUpdate)?;
+        self.renderer.render(&mut self.stdout, frame)?;
+        execute!(self.stdout, EndSynchronizedUpdate)
+    }
+}
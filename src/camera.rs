@@ -1,4 +1,50 @@
 use crate::bounding_box::BoundingBox;
+use crate::tile::Coord;
+use std::time::Duration;
+
+/// How quickly a smooth camera closes the distance to its target. It covers `1 - e^-rate` of the
+/// remaining distance each second.
+const SMOOTHING_RATE: f32 = 8.0;
+
+/// How the camera follows its target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Keeps the target in the exact center of the view.
+    Centered,
+    /// Only scrolls once the target leaves a box around the center of the view.
+    DeadZone,
+    /// Eases towards the centered position instead of jumping to it.
+    Smooth,
+}
+
+impl CameraMode {
+    pub const ALL: [CameraMode; 3] = [
+        CameraMode::Centered,
+        CameraMode::DeadZone,
+        CameraMode::Smooth,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CameraMode::Centered => "center",
+            CameraMode::DeadZone => "deadzone",
+            CameraMode::Smooth => "smooth",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        CameraMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// The mode after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let index = CameraMode::ALL
+            .iter()
+            .position(|mode| *mode == self)
+            .unwrap_or(0);
+        CameraMode::ALL[(index + 1) % CameraMode::ALL.len()]
+    }
+}
 
 pub struct Camera {
     pub x: i32,
@@ -7,6 +53,18 @@ pub struct Camera {
     pub height: u16,
 
     pub camera_view: BoundingBox,
+
+    pub mode: CameraMode,
+    /// The level area the view is kept inside of when `clamp` is set.
+    pub bounds: Option<BoundingBox>,
+    pub clamp: bool,
+    /// While set the camera ignores its target and only moves with `pan`.
+    pub free_look: bool,
+    /// Number of world tiles across and down shown in each screen cell.
+    pub zoom: u16,
+    /// The unrounded position of a smooth camera.
+    smooth_x: f32,
+    smooth_y: f32,
 }
 
 impl Camera {
@@ -22,40 +80,251 @@ impl Camera {
                 top: y,
                 bottom: y + height as i32,
             },
+            mode: CameraMode::Centered,
+            bounds: None,
+            clamp: false,
+            free_look: false,
+            zoom: 1,
+            smooth_x: x as f32,
+            smooth_y: y as f32,
         }
     }
-    pub fn world_to_screen(&self, world_x: i32, world_y: i32) -> Option<(u16, u16)> {
-        let screen_x = world_x - self.x;
-        let screen_y = world_y - self.y;
 
-        if screen_x >= 0
-            && screen_x < self.width as i32
-            && screen_y >= 0
-            && screen_y < self.height as i32
-        {
-            Some((screen_x as u16, screen_y as u16))
-        } else {
-            None
+    /// Moves the camera towards `target` the way the current mode does, `dt` after the previous
+    /// call. Nothing happens in free look.
+    pub fn follow(&mut self, target: Coord, dt: Duration) {
+        if self.free_look {
+            return;
+        }
+
+        let (target_x, target_y) = target;
+        let centered_x = target_x - self.view_width() / 2;
+        let centered_y = target_y - self.view_height() / 2;
+
+        match self.mode {
+            CameraMode::Centered => {
+                self.x = centered_x;
+                self.y = centered_y;
+            }
+            CameraMode::DeadZone => {
+                let zone_w = self.view_width() / 3;
+                let zone_h = self.view_height() / 3;
+                let left = self.x + (self.view_width() - zone_w) / 2;
+                let top = self.y + (self.view_height() - zone_h) / 2;
+
+                self.x += dead_zone_shift(target_x, left, left + zone_w);
+                self.y += dead_zone_shift(target_y, top, top + zone_h);
+            }
+            CameraMode::Smooth => {
+                let amount = 1.0 - (-SMOOTHING_RATE * dt.as_secs_f32()).exp();
+                self.smooth_x += (centered_x as f32 - self.smooth_x) * amount;
+                self.smooth_y += (centered_y as f32 - self.smooth_y) * amount;
+
+                // Settle exactly once close enough, so the view does not creep forever
+                if (centered_x as f32 - self.smooth_x).abs() < 0.5 {
+                    self.smooth_x = centered_x as f32;
+                }
+                if (centered_y as f32 - self.smooth_y).abs() < 0.5 {
+                    self.smooth_y = centered_y as f32;
+                }
+
+                self.x = self.smooth_x.round() as i32;
+                self.y = self.smooth_y.round() as i32;
+            }
         }
+
+        self.finish_move();
+    }
+
+    /// Jumps straight to the centered position on `target`, e.g. after a resize.
+    pub fn snap_to(&mut self, target: Coord) {
+        self.x = target.0 - self.view_width() / 2;
+        self.y = target.1 - self.view_height() / 2;
+        self.finish_move();
+    }
+
+    /// Scrolls the view by a number of tiles.
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.x += dx;
+        self.y += dy;
+        self.finish_move();
+    }
+
+    /// Number of world tiles the view spans horizontally.
+    pub fn view_width(&self) -> i32 {
+        self.width as i32 * self.zoom as i32
+    }
+
+    /// Number of world tiles the view spans vertically.
+    pub fn view_height(&self) -> i32 {
+        self.height as i32 * self.zoom as i32
     }
 
-    /// Returns whether a world rectangle intersects the camera.
-    pub fn is_visible(&self, world_x: i32, world_y: i32, width: u16, height: u16) -> bool {
-        let right = self.x + self.width as i32;
-        let bottom = self.y + self.height as i32;
+    /// The smallest zoom at which the whole of `bounds` fits in the view.
+    pub fn zoom_to_fit(&self, bounds: &BoundingBox) -> u16 {
+        let across = (bounds.right - bounds.left) as f32 / self.width.max(1) as f32;
+        let down = (bounds.bottom - bounds.top) as f32 / self.height.max(1) as f32;
 
-        !(world_x + width as i32 <= self.x
-            || world_x >= right
-            || world_y + height as i32 <= self.y
-            || world_y >= bottom)
+        across.max(down).ceil().max(1.0) as u16
+    }
+
+    fn finish_move(&mut self) {
+        // A zoomed out view is for surveying the level, so it never shows the void around it
+        if self.clamp || self.zoom > 1 {
+            if let Some(bounds) = &self.bounds {
+                let (width, height) = (self.view_width(), self.view_height());
+                self.x = clamp_axis(self.x, width, bounds.left, bounds.right);
+                self.y = clamp_axis(self.y, height, bounds.top, bounds.bottom);
+            }
+        }
+
+        // Keep easing from where the view actually is when it moved some other way
+        if self.smooth_x.round() as i32 != self.x {
+            self.smooth_x = self.x as f32;
+        }
+        if self.smooth_y.round() as i32 != self.y {
+            self.smooth_y = self.y as f32;
+        }
+
+        self.update_bbox();
     }
 
     pub fn update_bbox(&mut self) {
         self.camera_view = BoundingBox {
             left: self.x,
-            right: self.x + self.width as i32,
+            right: self.x + self.view_width(),
             top: self.y,
-            bottom: self.y + self.height as i32,
+            bottom: self.y + self.view_height(),
+        };
+    }
+}
+
+/// The cell of a view showing `origin` at its top left and `zoom` x `zoom` world tiles in each
+/// cell that a world position falls into. It may lie outside the view.
+pub fn world_to_view((x, y): Coord, (origin_x, origin_y): Coord, zoom: u16) -> (i32, i32) {
+    let zoom = zoom.max(1) as i32;
+    (
+        (x - origin_x).div_euclid(zoom),
+        (y - origin_y).div_euclid(zoom),
+    )
+}
+
+/// The top left world position of the block of tiles shown in a view cell, the reverse of
+/// `world_to_view`.
+pub fn view_to_world((x, y): (i32, i32), (origin_x, origin_y): Coord, zoom: u16) -> Coord {
+    let zoom = zoom.max(1) as i32;
+    (origin_x + x * zoom, origin_y + y * zoom)
+}
+
+/// How far a view has to scroll along one axis to bring `target` back inside `start..end`.
+fn dead_zone_shift(target: i32, start: i32, end: i32) -> i32 {
+    if target < start {
+        target - start
+    } else if target >= end {
+        target - end + 1
+    } else {
+        0
+    }
+}
+
+/// Keeps a view of `size` starting at `position` inside `start..end`, centering it when the
+/// level is smaller than the view.
+fn clamp_axis(position: i32, size: i32, start: i32, end: i32) -> i32 {
+    if end - start <= size {
+        start - (size - (end - start)) / 2
+    } else {
+        position.clamp(start, end - size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(33);
+
+    fn camera(mode: CameraMode) -> Camera {
+        let mut camera = Camera::new(0, 0, 30, 15);
+        camera.mode = mode;
+        camera
+    }
+
+    #[test]
+    fn test_dead_zone_only_scrolls_at_its_edge() {
+        let mut camera = camera(CameraMode::DeadZone);
+        camera.snap_to((15, 7));
+
+        camera.follow((18, 7), TICK);
+        assert_eq!((camera.x, camera.y), (0, 0));
+
+        camera.follow((21, 7), TICK);
+        assert_eq!((camera.x, camera.y), (2, 0));
+    }
+
+    #[test]
+    fn test_clamp_keeps_view_inside_bounds() {
+        let mut camera = camera(CameraMode::Centered);
+        camera.clamp = true;
+        camera.bounds = Some(BoundingBox {
+            left: 0,
+            right: 100,
+            top: 0,
+            bottom: 10,
+        });
+
+        camera.follow((2, 2), TICK);
+        // The level is shorter than the view, so it is centered vertically
+        assert_eq!((camera.x, camera.y), (0, -2));
+
+        camera.follow((99, 5), TICK);
+        assert_eq!(camera.x, 70);
+    }
+
+    #[test]
+    fn test_smooth_camera_eases_to_target() {
+        let mut camera = camera(CameraMode::Smooth);
+        camera.snap_to((15, 7));
+
+        camera.follow((35, 7), TICK);
+        assert!(camera.x > 0 && camera.x < 20);
+
+        for _ in 0..50 {
+            camera.follow((35, 7), TICK);
+        }
+        assert_eq!(camera.x, 20);
+    }
+
+    #[test]
+    fn test_zoom_aggregates_tiles() {
+        let mut camera = camera(CameraMode::Centered);
+        let level = BoundingBox {
+            left: 0,
+            right: 100,
+            top: 0,
+            bottom: 40,
         };
+        camera.zoom = camera.zoom_to_fit(&level);
+        camera.bounds = Some(level);
+        assert_eq!(camera.zoom, 4);
+
+        camera.follow((50, 20), TICK);
+
+        // The 120x60 view centers the 100x40 level
+        assert_eq!((camera.x, camera.y), (-10, -10));
+        let origin = (camera.x, camera.y);
+        assert_eq!(world_to_view((0, 0), origin, camera.zoom), (2, 2));
+        assert_eq!(world_to_view((3, 1), origin, camera.zoom), (3, 2));
+        assert_eq!(view_to_world((3, 2), origin, camera.zoom), (2, -2));
+    }
+
+    #[test]
+    fn test_free_look_ignores_target() {
+        let mut camera = camera(CameraMode::Centered);
+        camera.free_look = true;
+
+        camera.pan(3, -1);
+        camera.follow((100, 100), TICK);
+
+        assert_eq!((camera.x, camera.y), (3, -1));
     }
 }
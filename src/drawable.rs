@@ -1,16 +1,24 @@
+pub(crate) mod fixture;
 pub(crate) mod fps;
-pub(crate) mod room;
-pub(crate) mod tree;
+pub(crate) mod item;
 
 use crate::bounding_box::BoundingBox;
-use crate::frame::Frame;
-use crate::tile::{Coord, Tile};
-use std::collections::HashMap;
+use crate::frame::{Frame, Layer};
+use crate::lighting::LightSource;
 
 pub trait Drawable {
     fn draw(&self, frame: &mut Frame);
 
-    fn static_map(&self, collision_map: &mut HashMap<Coord, Tile>);
+    /// The layer this drawable is composited on. `Game::draw` selects it before calling `draw`.
+    fn layer(&self) -> Layer {
+        Layer::Terrain
+    }
+
+    /// The light this drawable gives off, if any.
+    fn light(&self) -> Option<LightSource> {
+        None
+    }
+
     fn bound_box(&self) -> BoundingBox;
     fn as_any(&self) -> &dyn std::any::Any;
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
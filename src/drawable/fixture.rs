@@ -0,0 +1,83 @@
+use crate::bounding_box::BoundingBox;
+use crate::drawable::Drawable;
+use crate::frame::{Cell, Frame, Layer};
+use crate::lighting::{Light, LightSource};
+use crate::theme::Role;
+use crossterm::style::Attributes;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixtureKind {
+    /// A torch mounted on a wall.
+    Sconce,
+    /// Glowing fungus growing on the cave floor.
+    Fungus,
+}
+
+/// A fixture is a fixed part of the level that gives off light.
+pub struct Fixture {
+    pub x: i32,
+    pub y: i32,
+    pub kind: FixtureKind,
+}
+
+impl Fixture {
+    pub fn new(x: i32, y: i32, kind: FixtureKind) -> Self {
+        Fixture { x, y, kind }
+    }
+}
+
+impl Drawable for Fixture {
+    fn draw(&self, frame: &mut Frame) {
+        let glyphs = frame.glyphs();
+        let (ch, role) = match self.kind {
+            FixtureKind::Sconce => (glyphs.sconce, Role::Sconce),
+            FixtureKind::Fungus => (glyphs.fungus, Role::Fungus),
+        };
+
+        frame.set_world_cell(
+            self.x,
+            self.y,
+            Cell {
+                ch,
+                fg: frame.color(role),
+                bg: None,
+                attrs: Attributes::none(),
+                is_walkable: self.kind == FixtureKind::Fungus,
+            },
+        );
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Items
+    }
+
+    fn light(&self) -> Option<LightSource> {
+        let (radius, color) = match self.kind {
+            FixtureKind::Sconce => (6, Light::new(1.0, 0.7, 0.35)),
+            FixtureKind::Fungus => (2, Light::new(0.25, 0.8, 0.6)),
+        };
+
+        Some(LightSource {
+            position: (self.x, self.y),
+            radius,
+            color,
+        })
+    }
+
+    fn bound_box(&self) -> BoundingBox {
+        BoundingBox {
+            left: self.x,
+            right: self.x + 1,
+            top: self.y,
+            bottom: self.y + 1,
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
@@ -1,5 +1,6 @@
 use crate::drawable::{BoundingBox, Drawable};
 use crate::frame::Frame;
+use crossterm::style::Attributes;
 
 pub struct Fps {
     pub last_frame: std::time::Instant,
@@ -28,16 +29,10 @@ impl Drawable for Fps {
             &format!("FPS: {}", self.fps),
             None,
             None,
+            Attributes::none(),
         );
     }
 
-    fn static_map(
-        &self,
-        _collision_map: &mut std::collections::HashMap<(i32, i32), crate::tile::Tile>,
-    ) {
-        // Do nothing
-    }
-
     fn bound_box(&self) -> BoundingBox {
         BoundingBox {
             left: 0,
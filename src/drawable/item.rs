@@ -0,0 +1,86 @@
+use crate::bounding_box::BoundingBox;
+use crate::drawable::Drawable;
+use crate::frame::{Cell, Frame, Layer};
+use crate::theme::Role;
+use crossterm::style::Attributes;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemKind {
+    /// Healing potions, `amount` of them.
+    Potions,
+    /// Oil for the torch, worth `amount` seconds of light.
+    Oil,
+}
+
+impl ItemKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemKind::Potions => "potions",
+            ItemKind::Oil => "oil",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "potions" => Some(ItemKind::Potions),
+            "oil" => Some(ItemKind::Oil),
+            _ => None,
+        }
+    }
+}
+
+/// Loot lying on the floor, picked up by walking over it.
+pub struct Item {
+    pub x: i32,
+    pub y: i32,
+    pub kind: ItemKind,
+    pub amount: u32,
+}
+
+impl Item {
+    pub fn new(x: i32, y: i32, kind: ItemKind, amount: u32) -> Self {
+        Item { x, y, kind, amount }
+    }
+}
+
+impl Drawable for Item {
+    fn draw(&self, frame: &mut Frame) {
+        let ch = match self.kind {
+            ItemKind::Potions => '!',
+            ItemKind::Oil => '%',
+        };
+
+        frame.set_world_cell(
+            self.x,
+            self.y,
+            Cell {
+                ch,
+                fg: frame.color(Role::Item),
+                bg: None,
+                attrs: Attributes::none(),
+                is_walkable: true,
+            },
+        );
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Items
+    }
+
+    fn bound_box(&self) -> BoundingBox {
+        BoundingBox {
+            left: self.x,
+            right: self.x + 1,
+            top: self.y,
+            bottom: self.y + 1,
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
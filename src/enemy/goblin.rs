@@ -1,90 +1,210 @@
 use crate::bounding_box::BoundingBox;
-use crate::camera::Camera;
 use crate::drawable::Drawable;
-use crate::frame::{Cell, Frame};
+use crate::frame::{Cell, Frame, Layer};
 use crate::health::Health;
-use crate::pathfinding::{bounding_box_for_path, find_path, Point};
+use crate::pathfinding::{bounding_box_for_path, find_weighted_path, Point};
 use crate::player::Player;
-use crate::tile::{Coord, Tile};
-use crossterm::style::Color;
-use std::collections::HashMap;
+use crate::scheduler::{self, Action, Energy};
+use crate::tile::TileMap;
+use crate::theme::Role;
+use crossterm::style::Attributes;
+use std::time::Duration;
+
+/// The kinds of goblin, from the weakest to the most dangerous.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoblinKind {
+    /// Quick on its feet but easy to kill.
+    Scout,
+    Warrior,
+    /// Slow, but takes a beating and hits hard.
+    Brute,
+}
+
+impl GoblinKind {
+    pub const ALL: [GoblinKind; 3] = [GoblinKind::Scout, GoblinKind::Warrior, GoblinKind::Brute];
+
+    /// The name used for the kind in save files.
+    pub fn name(self) -> &'static str {
+        match self {
+            GoblinKind::Scout => "scout",
+            GoblinKind::Warrior => "warrior",
+            GoblinKind::Brute => "brute",
+        }
+    }
+
+    /// What the game calls a goblin of this kind.
+    pub fn noun(self) -> &'static str {
+        match self {
+            GoblinKind::Scout => "goblin scout",
+            GoblinKind::Warrior => "goblin",
+            GoblinKind::Brute => "goblin brute",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        GoblinKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// The name with an article, for saying what killed the player.
+    pub fn described(self) -> &'static str {
+        match self {
+            GoblinKind::Scout => "a goblin scout",
+            GoblinKind::Warrior => "a goblin",
+            GoblinKind::Brute => "a goblin brute",
+        }
+    }
+
+    fn glyph(self) -> char {
+        match self {
+            GoblinKind::Scout => 'g',
+            GoblinKind::Warrior => 'G',
+            GoblinKind::Brute => 'B',
+        }
+    }
+
+    fn max_health(self) -> i32 {
+        match self {
+            GoblinKind::Scout => 6,
+            GoblinKind::Warrior => 10,
+            GoblinKind::Brute => 16,
+        }
+    }
+
+    fn damage(self) -> i32 {
+        match self {
+            GoblinKind::Scout | GoblinKind::Warrior => 1,
+            GoblinKind::Brute => 3,
+        }
+    }
+
+    fn speed(self) -> i32 {
+        match self {
+            GoblinKind::Scout => scheduler::SPEED_FAST,
+            GoblinKind::Warrior => scheduler::SPEED_NORMAL,
+            GoblinKind::Brute => scheduler::SPEED_SLOW,
+        }
+    }
+}
 
 pub struct Goblin {
     pub x: i32,
     pub y: i32,
+    pub kind: GoblinKind,
     pub health: Health,
-    move_cooldown: u32,
+    pub energy: Energy,
     current_path: Option<Vec<Point>>,
     pub debug_mode: bool,
 }
 
 impl Goblin {
     pub fn new(x: i32, y: i32) -> Self {
+        Goblin::of_kind(x, y, GoblinKind::Warrior)
+    }
+
+    pub fn of_kind(x: i32, y: i32, kind: GoblinKind) -> Self {
         Goblin {
             x,
             y,
-            move_cooldown: 0,
+            kind,
+            energy: Energy::ready(kind.speed()),
             current_path: None,
             debug_mode: true,
-            health: Health::new(10),
+            health: Health::new(kind.max_health()),
         }
     }
 
     pub fn attack(&self, player: &mut Player) {
-        player.health.take_damage(1);
+        player.health.take_damage(self.kind.damage());
     }
 
-    pub fn update(&mut self, static_map: &HashMap<Coord, Tile>, player: &Player) {
-        if self.move_cooldown > 0 {
-            self.move_cooldown -= 1;
+    /// Gains the energy `dt` is worth in real-time mode and acts as often as it allows.
+    /// Returns how many times it attacked the player.
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        static_map: &TileMap,
+        player: &mut Player,
+    ) -> usize {
+        let mut attacks = 0;
+        self.energy.gain(dt.as_secs_f32() * scheduler::TICKS_PER_SECOND);
 
-            return;
+        while self.energy.can_act() && player.health.is_alive() {
+            let action = self.act(static_map, player);
+            self.spend(action, static_map);
+            attacks += (action == Action::Attack) as usize;
         }
 
-        if self.current_path.is_none() || self.move_cooldown == 0 {
-            let start = Point {
-                x: self.x,
-                y: self.y,
-            };
-            let goal = Point {
-                x: player.x,
-                y: player.y,
-            };
+        attacks
+    }
 
-            let is_walkable = |p: Point| {
-                if let Some(tile) = static_map.get(&(p.x, p.y)) {
-                    return tile.is_walkable();
-                }
+    /// Takes the goblin's turn: it attacks the player when next to them, and otherwise takes
+    /// one step along a path towards them. Returns what it did, so its energy can be spent.
+    pub fn act(&mut self, static_map: &TileMap, player: &mut Player) -> Action {
+        let start = Point {
+            x: self.x,
+            y: self.y,
+        };
+        let goal = Point {
+            x: player.x,
+            y: player.y,
+        };
 
-                return true;
-            };
+        // Goblins reach diagonally, just like the player does
+        if start.chebyshev_distance(&goal) <= 1 {
+            self.attack(player);
+            self.current_path = None;
 
-            self.current_path = find_path(start, goal, is_walkable);
+            return Action::Attack;
         }
 
-        // Move along the path if we have one
-        if let Some(path) = &self.current_path {
-            if path.len() > 1 { // If we have more than just our current position
-                 // let next = path[1];  // Get the next position
-                 // self.x = next.x;
-                 // self.y = next.y;
-                 // self.current_path = Some(path[1..].to_vec());  // Update path
-            } else {
-                self.current_path = None; // Clear path if we've reached the end
+        // Goblins would rather walk around water and rubble than wade through it
+        let cost = |p: Point| {
+            let position = (p.x, p.y);
+            static_map
+                .is_walkable(position)
+                .then(|| static_map.move_cost(position))
+        };
+
+        self.current_path = find_weighted_path(start, goal, cost);
+
+        // Step onto the next point of the path, which is never the player's own position
+        if let Some(path) = self.current_path.as_mut() {
+            if path.len() > 2 {
+                path.remove(0);
+                self.x = path[0].x;
+                self.y = path[0].y;
+
+                return Action::Move;
             }
         }
 
-        self.move_cooldown = 10; // Wait 10 frames before next move
-
+        Action::Wait
+    }
 
+    /// Spends the energy for an action the goblin just took. A move costs more when it ended
+    /// on slow ground.
+    pub fn spend(&mut self, action: Action, static_map: &TileMap) {
+        match action {
+            Action::Move => self
+                .energy
+                .spend_times(action, static_map.move_cost((self.x, self.y))),
+            _ => self.energy.spend(action),
+        }
     }
 
     pub fn draw_health(&self, frame: &mut Frame) {
         let max_hearts = self.health.get_max();
         let filled_hearts = self.health.get_current();
 
-        let hearts = format!("{}{}", "♥".to_string().repeat(filled_hearts as usize),
-                             "♡".repeat((max_hearts - filled_hearts) as usize));
+        let glyphs = frame.glyphs();
+        let hearts = format!(
+            "{}{}",
+            glyphs.heart_full.to_string().repeat(filled_hearts as usize),
+            glyphs
+                .heart_empty
+                .to_string()
+                .repeat((max_hearts - filled_hearts) as usize)
+        );
 
         // Draw health bar, centered on goblin by calculating the middle of the health bar
         let health_bar_x = self.x - (max_hearts / 2);
@@ -93,8 +213,9 @@ impl Goblin {
         for (i, ch) in hearts.chars().enumerate() {
             frame.set_world_cell(health_bar_x + i as i32, health_bar_y, Cell {
                 ch,
-                fg: Some(Color::Blue),
+                fg: frame.color(Role::EnemyHealth),
                 bg: None,
+                attrs: Attributes::none(),
                 is_walkable: true,
             });
         }
@@ -118,23 +239,38 @@ impl Goblin {
 
 impl Drawable for Goblin {
     fn draw(&self, frame: &mut Frame) {
-        frame.set_world_char(self.x, self.y, 'G');
+        frame.set_layer(Layer::Actors);
+        frame.set_world_cell(
+            self.x,
+            self.y,
+            Cell {
+                ch: self.kind.glyph(),
+                fg: frame.color(Role::Enemy),
+                bg: None,
+                attrs: Attributes::none(),
+                is_walkable: true,
+            },
+        );
 
+        frame.set_layer(Layer::Effects);
         self.draw_health(frame);
 
         if self.debug_mode {
+            frame.set_layer(Layer::Items);
+
             if let Some(path) = &self.current_path {
                 let skip_first = path.len() > 1;
 
                 // Skip first and last points
-                for point in path.iter().skip(if skip_first { 1 } else { 0 }).take(path.len() - 2) {
+                for point in path.iter().skip(if skip_first { 1 } else { 0 }).take(path.len().saturating_sub(2)) {
                     frame.set_world_cell(
                         point.x,
                         point.y,
                         crate::frame::Cell {
-                            ch: '·',
-                            fg: Some(Color::Yellow),
+                            ch: frame.glyphs().path,
+                            fg: frame.color(Role::DebugPath),
                             bg: None,
+                            attrs: Attributes::none(),
                             is_walkable: true,
                         },
                     );
@@ -143,11 +279,8 @@ impl Drawable for Goblin {
         }
     }
 
-    fn static_map(
-        &self,
-        _collision_map: &mut std::collections::HashMap<(i32, i32), crate::tile::Tile>,
-    ) {
-        // Do nothing
+    fn layer(&self) -> Layer {
+        Layer::Actors
     }
 
     fn bound_box(&self) -> BoundingBox {
@@ -0,0 +1,204 @@
+use crate::tile::Coord;
+use std::collections::HashSet;
+
+/// How much of a world position the player currently knows about, ordered from most to least.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Visibility {
+    /// In the field of view right now.
+    Visible,
+    /// Seen before but not currently in view.
+    Remembered,
+    /// Never seen.
+    Unseen,
+}
+
+/// A slope as an exact fraction, so the shadowcasting stays symmetric without floating point
+/// rounding.
+#[derive(Clone, Copy, Debug)]
+struct Slope {
+    numerator: i32,
+    denominator: i32,
+}
+
+impl Slope {
+    fn new(numerator: i32, denominator: i32) -> Self {
+        Slope {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// The slope through the near edge of the tile at `depth`, `col`.
+    fn of_tile(depth: i32, col: i32) -> Self {
+        Slope::new(2 * col - 1, 2 * depth)
+    }
+}
+
+/// One row of a quadrant scan, between two slopes.
+#[derive(Clone, Copy, Debug)]
+struct Row {
+    depth: i32,
+    start: Slope,
+    end: Slope,
+}
+
+impl Row {
+    fn min_col(&self) -> i32 {
+        // depth * start rounded to the nearest column, ties rounding up
+        (2 * self.depth * self.start.numerator + self.start.denominator)
+            .div_euclid(2 * self.start.denominator)
+    }
+
+    fn max_col(&self) -> i32 {
+        // depth * end rounded to the nearest column, ties rounding down
+        -(-(2 * self.depth * self.end.numerator - self.end.denominator))
+            .div_euclid(2 * self.end.denominator)
+    }
+
+    fn next(&self) -> Row {
+        Row {
+            depth: self.depth + 1,
+            ..*self
+        }
+    }
+
+    /// Whether the center of the tile in column `col` lies between the row's slopes. Only those
+    /// floor tiles are revealed, which is what makes the field of view symmetric.
+    fn is_symmetric(&self, col: i32) -> bool {
+        col * self.start.denominator >= self.depth * self.start.numerator
+            && col * self.end.denominator <= self.depth * self.end.numerator
+    }
+}
+
+/// The four quadrants around the origin, each scanned as rows moving away from it.
+#[derive(Clone, Copy)]
+enum Quadrant {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Quadrant {
+    fn transform(self, origin: Coord, depth: i32, col: i32) -> Coord {
+        let (x, y) = origin;
+
+        match self {
+            Quadrant::North => (x + col, y - depth),
+            Quadrant::South => (x + col, y + depth),
+            Quadrant::East => (x + depth, y + col),
+            Quadrant::West => (x - depth, y + col),
+        }
+    }
+}
+
+/// Computes the positions visible from `origin` within `radius` tiles using symmetric
+/// shadowcasting: if A can see B then B can see A. Opaque tiles are visible themselves but hide
+/// what lies behind them.
+pub fn compute_fov(origin: Coord, radius: i32, is_opaque: impl Fn(Coord) -> bool) -> HashSet<Coord> {
+    let mut visible = HashSet::new();
+    visible.insert(origin);
+
+    for quadrant in [
+        Quadrant::North,
+        Quadrant::East,
+        Quadrant::South,
+        Quadrant::West,
+    ] {
+        let first_row = Row {
+            depth: 1,
+            start: Slope::new(-1, 1),
+            end: Slope::new(1, 1),
+        };
+
+        scan(origin, radius, quadrant, first_row, &is_opaque, &mut visible);
+    }
+
+    visible
+}
+
+fn scan(
+    origin: Coord,
+    radius: i32,
+    quadrant: Quadrant,
+    mut row: Row,
+    is_opaque: &impl Fn(Coord) -> bool,
+    visible: &mut HashSet<Coord>,
+) {
+    if row.depth > radius {
+        return;
+    }
+
+    let mut previous_is_wall: Option<bool> = None;
+
+    for col in row.min_col()..=row.max_col() {
+        let position = quadrant.transform(origin, row.depth, col);
+        let is_wall = is_opaque(position);
+        let in_radius = row.depth * row.depth + col * col <= radius * radius;
+
+        if in_radius && (is_wall || row.is_symmetric(col)) {
+            visible.insert(position);
+        }
+
+        if previous_is_wall == Some(true) && !is_wall {
+            row.start = Slope::of_tile(row.depth, col);
+        }
+
+        if previous_is_wall == Some(false) && is_wall {
+            let mut next_row = row.next();
+            next_row.end = Slope::of_tile(row.depth, col);
+            scan(origin, radius, quadrant, next_row, is_opaque, visible);
+        }
+
+        previous_is_wall = Some(is_wall);
+    }
+
+    if previous_is_wall == Some(false) {
+        scan(origin, radius, quadrant, row.next(), is_opaque, visible);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_field_is_a_disc() {
+        let visible = compute_fov((0, 0), 3, |_| false);
+
+        assert!(visible.contains(&(0, 0)));
+        assert!(visible.contains(&(3, 0)));
+        assert!(visible.contains(&(2, 2)));
+        assert!(!visible.contains(&(3, 3)));
+        assert!(!visible.contains(&(4, 0)));
+    }
+
+    #[test]
+    fn test_wall_hides_what_is_behind_it() {
+        let wall: HashSet<Coord> = (-1..=1).map(|y| (2, y)).collect();
+        let visible = compute_fov((0, 0), 8, |p| wall.contains(&p));
+
+        assert!(visible.contains(&(2, 0)));
+        assert!(!visible.contains(&(3, 0)));
+        assert!(!visible.contains(&(5, 1)));
+        assert!(visible.contains(&(0, 5)));
+    }
+
+    #[test]
+    fn test_field_of_view_is_symmetric() {
+        let pillars: HashSet<Coord> = [(2, 1), (4, -2), (-3, 2), (1, -3), (5, 3)]
+            .into_iter()
+            .collect();
+        let is_opaque = |p: Coord| pillars.contains(&p);
+        let origin = (0, 0);
+
+        for target in compute_fov(origin, 10, is_opaque) {
+            if pillars.contains(&target) {
+                continue;
+            }
+
+            let back = compute_fov(target, 10, is_opaque);
+            assert!(back.contains(&origin), "{:?} sees origin but not back", target);
+        }
+    }
+}
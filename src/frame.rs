@@ -1,123 +1,594 @@
-use crate::camera::Camera;
-use crossterm::style::Color;
+use crate::camera;
+use crate::fov::Visibility;
+use crate::glyphs::{self, GlyphSet};
+use crate::layout::Rect;
+use crate::lighting::{self, Light};
+use crate::theme::{Role, Theme};
+use crossterm::style::{Attribute, Attributes, Color};
 use std::cmp;
+use std::rc::Rc;
+use unicode_width::UnicodeWidthChar;
 
+/// Glyph of a cell that only contributes its colors, letting the glyph of the layer beneath show
+/// through. A transparent cell with only a background color tints whatever is below it.
+pub const TRANSPARENT: char = '\0';
+
+/// Glyph of the right half of a two-column character. The terminal advances past it when the
+/// wide character to its left is printed, so it is never drawn itself.
+pub const CONTINUATION: char = '\u{1}';
+
+const BLANK: Cell = Cell {
+    ch: ' ',
+    fg: None,
+    bg: None,
+    attrs: Attributes::none(),
+    is_walkable: true,
+};
+
+/// Layers a frame is composited from, listed from bottom to top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Terrain,
+    Items,
+    Actors,
+    Effects,
+    Ui,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 5] = [
+        Layer::Terrain,
+        Layer::Items,
+        Layer::Actors,
+        Layer::Effects,
+        Layer::Ui,
+    ];
+}
+
+#[derive(Clone)]
 pub struct Frame {
     pub width: u16,
     pub height: u16,
 
+    /// The composited result of all layers, which is what gets presented.
     pub buffer: Vec<Cell>,
+    layers: Vec<Vec<Option<Cell>>>,
+    layer: Layer,
+    glyphs: &'static GlyphSet,
+    theme: Rc<Theme>,
+    /// The screen region world coordinates are drawn into.
+    viewport: Rect,
+    /// When set, writes outside this region are dropped.
+    clip: Option<Rect>,
     cam_x: i32,
     cam_y: i32,
+    /// Number of world tiles across and down that share one viewport cell.
+    zoom: u16,
 }
 
 impl Frame {
     pub fn new(cam_x: i32, cam_y: i32, width: u16, height: u16) -> Self {
+        let size = width as usize * height as usize;
+
         Frame {
             width,
             height,
-            buffer: vec![
-                Cell {
-                    ch: ' ',
-                    fg: None,
-                    bg: None,
-                    is_walkable: true,
-                };
-                (width * height) as usize
-            ],
+            buffer: vec![BLANK; size],
+            layers: vec![vec![None; size]; Layer::ALL.len()],
+            layer: Layer::Terrain,
+            glyphs: &glyphs::UNICODE,
+            theme: Rc::new(Theme::default()),
+            viewport: Rect::new(0, 0, width, height),
+            clip: None,
             cam_x,
             cam_y,
+            zoom: 1,
         }
     }
 
-    pub fn is_walkable(&self, x: u16, y: u16) -> bool {
-        if x < self.width && y < self.height {
-            let index = (y * self.width + x) as usize;
-            self.buffer[index].is_walkable
-        } else {
-            false
+    /// Rebuilds the buffers for a new terminal size, leaving every cell blank.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let size = width as usize * height as usize;
+
+        self.width = width;
+        self.height = height;
+        self.buffer = vec![BLANK; size];
+        self.layers = vec![vec![None; size]; Layer::ALL.len()];
+        self.viewport = self.viewport.intersection(&Rect::new(0, 0, width, height));
+    }
+
+    /// Sets the screen region that world coordinates are drawn into, with the camera position
+    /// at its top left corner. Nothing drawn through `set_world_cell` leaves this region.
+    pub fn set_viewport(&mut self, viewport: Rect) {
+        self.viewport = viewport.intersection(&Rect::new(0, 0, self.width, self.height));
+    }
+
+    /// Restricts all drawing to `clip`, or lifts the restriction with `None`.
+    pub fn set_clip(&mut self, clip: Option<Rect>) {
+        self.clip = clip;
+    }
+
+    pub fn clip(&self) -> Option<Rect> {
+        self.clip
+    }
+
+    /// Overwrites every cell of `area` on the current layer with an opaque blank, hiding
+    /// whatever lower layers drew there.
+    pub fn clear_rect(&mut self, area: Rect) {
+        for y in area.y..area.bottom() {
+            for x in area.x..area.right() {
+                self.set_char(x, y, ' ');
+            }
         }
     }
 
-    pub fn set_world_char(&mut self, world_x: i32, world_y: i32, ch: char) {
-        self.set_world_cell(
-            world_x,
-            world_y,
-            Cell {
-                ch,
-                fg: None,
-                bg: None,
-                is_walkable: true,
-            },
-        )
+    /// Moves the world position shown at the top left of the map.
+    pub fn set_camera(&mut self, cam_x: i32, cam_y: i32) {
+        self.cam_x = cam_x;
+        self.cam_y = cam_y;
     }
 
-    pub fn set_world_cell(&mut self, world_x: i32, world_y: i32, cell: Cell) {
-        let screen_x = world_x - self.cam_x;
-        let screen_y = world_y - self.cam_y;
+    /// Shows `zoom` x `zoom` world tiles in each viewport cell. Every tile of a block is drawn to
+    /// the same cell, where the most important one wins: on each layer impassable cells such as
+    /// walls beat passable ones, and later draws beat earlier ones of equal importance. Effects
+    /// are too small to make out and are not drawn at all when zoomed out.
+    pub fn set_zoom(&mut self, zoom: u16) {
+        self.zoom = zoom.max(1);
+    }
+
+    /// Selects the layer that subsequent draw calls write into.
+    pub fn set_layer(&mut self, layer: Layer) {
+        self.layer = layer;
+    }
 
-        let game_height = self.height - (self.height / 3);
+    /// Selects the glyph set drawables should use, enabling ASCII fallback when it asks for it.
+    pub fn set_glyphs(&mut self, glyphs: &'static GlyphSet) {
+        self.glyphs = glyphs;
+    }
+
+    pub fn glyphs(&self) -> &'static GlyphSet {
+        self.glyphs
+    }
+
+    pub fn set_theme(&mut self, theme: Rc<Theme>) {
+        self.theme = theme;
+    }
+
+    /// Resolves a color role through the current theme.
+    pub fn color(&self, role: Role) -> Option<Color> {
+        self.theme.color(role)
+    }
+
+    pub fn set_world_cell(&mut self, world_x: i32, world_y: i32, cell: Cell) {
+        let (view_x, view_y) =
+            camera::world_to_view((world_x, world_y), (self.cam_x, self.cam_y), self.zoom);
 
-        if screen_x < 0 || screen_x as u16 >= self.width {
+        if view_x < 0 || view_x >= self.viewport.width as i32 {
             return;
         }
-        if screen_y < 0 || screen_y as u16 >= game_height {
+        if view_y < 0 || view_y >= self.viewport.height as i32 {
             return;
         }
 
-        let idx = (screen_y as u16 * self.width + screen_x as u16) as usize;
-        self.buffer[idx] = cell;
+        let x = self.viewport.x + view_x as u16;
+        let y = self.viewport.y + view_y as u16;
+
+        if self.zoom > 1 {
+            if self.layer == Layer::Effects {
+                return;
+            }
+
+            let existing = self.layers[self.layer as usize][self.index(x, y)];
+            if existing.is_some_and(|existing| !existing.is_walkable && cell.is_walkable) {
+                return;
+            }
+        }
+
+        self.put(x, y, cell);
     }
 
-    pub fn set(&mut self, x: u16, y: u16, ch: char, fg: Option<Color>, bg: Option<Color>) {
-        if x < self.width && y < self.height {
-            let index = (y * self.width + x) as usize;
-            self.buffer[index] = Cell {
-                ch,
-                fg,
-                bg,
-                is_walkable: true,
-            };
+    /// Hides what the player cannot see, looking every viewport cell up by its world position,
+    /// or by the best known tile of its block when zoomed out. Unseen cells lose everything
+    /// below the UI layer. Remembered cells lose their actors and effects, and keep their
+    /// terrain and items dimmed in the `remembered` color.
+    pub fn apply_fog(
+        &mut self,
+        remembered: Option<Color>,
+        visibility: impl Fn(i32, i32) -> Visibility,
+    ) {
+        let viewport = self.viewport;
+
+        for y in viewport.y..viewport.bottom() {
+            for x in viewport.x..viewport.right() {
+                let index = self.index(x, y);
+                let known = self
+                    .world_block(x, y)
+                    .map(|(world_x, world_y)| visibility(world_x, world_y))
+                    .min()
+                    .unwrap_or(Visibility::Unseen);
+
+                let hidden: &[Layer] = match known {
+                    Visibility::Visible => continue,
+                    Visibility::Remembered => &[Layer::Actors, Layer::Effects],
+                    Visibility::Unseen => &[
+                        Layer::Terrain,
+                        Layer::Items,
+                        Layer::Actors,
+                        Layer::Effects,
+                    ],
+                };
+
+                for layer in hidden {
+                    self.layers[*layer as usize][index] = None;
+                }
+
+                for layer in [Layer::Terrain, Layer::Items] {
+                    if let Some(cell) = &mut self.layers[layer as usize][index] {
+                        cell.fg = remembered.or(cell.fg);
+                        cell.bg = None;
+                        cell.attrs = Attributes::from(Attribute::Dim);
+                    }
+                }
+
+                self.buffer[index] = self.composite(index);
+            }
+
+            for x in viewport.x..viewport.right() {
+                self.repair_wide(x, y);
+            }
         }
     }
 
-    pub fn set_char(&mut self, x: u16, y: u16, ch: char) {
-        self.set(x, y, ch, None, None);
+    /// Shades the colors of everything below the UI layer by the light at each viewport cell's
+    /// world position, or by the brightest tile of its block when zoomed out.
+    pub fn apply_lighting(&mut self, light_at: impl Fn(i32, i32) -> Light) {
+        let viewport = self.viewport;
+
+        for y in viewport.y..viewport.bottom() {
+            for x in viewport.x..viewport.right() {
+                let light = self
+                    .world_block(x, y)
+                    .map(|(world_x, world_y)| light_at(world_x, world_y))
+                    .max_by(|a, b| (a.r + a.g + a.b).total_cmp(&(b.r + b.g + b.b)))
+                    .unwrap_or(Light::BLACK);
+                let index = self.index(x, y);
+
+                for layer in [Layer::Terrain, Layer::Items, Layer::Actors, Layer::Effects] {
+                    if let Some(cell) = &mut self.layers[layer as usize][index] {
+                        // A transparent cell without a foreground lets the one beneath show
+                        if cell.ch != TRANSPARENT || cell.fg.is_some() {
+                            cell.fg = Some(lighting::shade(cell.fg, light));
+                        }
+                        cell.bg = cell.bg.map(|bg| lighting::shade(Some(bg), light));
+                    }
+                }
+
+                self.buffer[index] = self.composite(index);
+            }
+        }
     }
 
-    pub fn get_char(&self, x: u16, y: u16) -> Option<Cell> {
+    pub fn set(
+        &mut self,
+        x: u16,
+        y: u16,
+        ch: char,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        attrs: Attributes,
+    ) {
         if x < self.width && y < self.height {
-            let index = (y * self.width + x) as usize;
-            Some(self.buffer[index])
-        } else {
-            None
+            self.put(
+                x,
+                y,
+                Cell {
+                    ch,
+                    fg,
+                    bg,
+                    attrs,
+                    is_walkable: true,
+                },
+            );
         }
     }
 
+    pub fn set_char(&mut self, x: u16, y: u16, ch: char) {
+        self.set(x, y, ch, None, None, Attributes::none());
+    }
+
     pub fn clear(&mut self) {
-        for cell in self.buffer.iter_mut() {
-            cell.ch = ' ';
-            cell.fg = None;
-            cell.bg = None;
+        for layer in self.layers.iter_mut() {
+            layer.fill(None);
         }
+        self.buffer.fill(BLANK);
     }
 
-    pub fn draw_text(&mut self, x: u16, y: u16, text: &str, fg: Option<Color>, bg: Option<Color>) {
+    /// Draws text starting at `x`, advancing by each character's display width. Wide characters
+    /// take two columns and are dropped if only one column is left; zero-width characters are
+    /// skipped since a cell holds a single character.
+    pub fn draw_text(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        attrs: Attributes,
+    ) {
         let mut col = x;
         for ch in text.chars() {
-            self.set(col, y, ch, fg, bg);
-            col += 1;
-            if col >= self.width {
+            let ch = self.displayable(ch);
+            let width = ch.width().unwrap_or(0) as u16;
+
+            if width == 0 {
+                continue;
+            }
+            if col + width > self.width {
                 break;
             }
+
+            self.set(col, y, ch, fg, bg, attrs);
+            if width == 2 {
+                self.set(col + 1, y, CONTINUATION, fg, bg, attrs);
+            }
+
+            col += width;
+        }
+    }
+
+    /// Returns the number of terminal columns `text` occupies when drawn with the current glyphs.
+    pub fn text_width(&self, text: &str) -> u16 {
+        text.chars()
+            .map(|ch| self.displayable(ch).width().unwrap_or(0) as u16)
+            .sum()
+    }
+
+    fn displayable(&self, ch: char) -> char {
+        if self.glyphs.ascii_only && ch != TRANSPARENT && ch != CONTINUATION {
+            glyphs::ascii_fallback(ch)
+        } else {
+            ch
+        }
+    }
+
+    /// The world positions shown at a screen cell of the viewport, which is a single one unless
+    /// zoomed out.
+    fn world_block(&self, x: u16, y: u16) -> impl Iterator<Item = (i32, i32)> {
+        let zoom = self.zoom as i32;
+        let view = ((x - self.viewport.x) as i32, (y - self.viewport.y) as i32);
+        let (left, top) = camera::view_to_world(view, (self.cam_x, self.cam_y), self.zoom);
+
+        (top..top + zoom).flat_map(move |world_y| (left..left + zoom).map(move |x| (x, world_y)))
+    }
+
+    fn index(&self, x: u16, y: u16) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Writes a cell into the current layer and recomposites that position and its neighbours,
+    /// since a wide character depends on the cell to its right.
+    fn put(&mut self, x: u16, y: u16, mut cell: Cell) {
+        if self.clip.is_some_and(|clip| !clip.contains(x, y)) {
+            return;
+        }
+
+        cell.ch = self.displayable(cell.ch);
+
+        let index = self.index(x, y);
+        self.layers[self.layer as usize][index] = Some(cell);
+
+        let first = x.saturating_sub(1);
+        let last = cmp::min(x + 1, self.width - 1);
+
+        for col in first..=last {
+            let index = self.index(col, y);
+            self.buffer[index] = self.composite(index);
+        }
+
+        for col in first..=last {
+            self.repair_wide(col, y);
+        }
+    }
+
+    /// Blanks halves of wide characters that lost their partner, e.g. when a layer above covers
+    /// only one of the two columns.
+    fn repair_wide(&mut self, x: u16, y: u16) {
+        let index = self.index(x, y);
+        let ch = self.buffer[index].ch;
+
+        if ch == CONTINUATION {
+            let leader_is_wide = x > 0 && is_wide(self.buffer[index - 1].ch);
+            if !leader_is_wide {
+                self.buffer[index].ch = ' ';
+            }
+        } else if is_wide(ch) {
+            let has_continuation = x + 1 < self.width && self.buffer[index + 1].ch == CONTINUATION;
+            if !has_continuation {
+                self.buffer[index].ch = ' ';
+            }
         }
     }
+
+    /// Stacks every layer at `index` from bottom to top. Opaque glyphs replace what is beneath
+    /// them, keeping the lower background when they have none of their own, while transparent
+    /// cells only override colors and add their attributes.
+    fn composite(&self, index: usize) -> Cell {
+        let mut result = BLANK;
+
+        for layer in &self.layers {
+            let Some(cell) = layer[index] else {
+                continue;
+            };
+
+            if cell.ch == TRANSPARENT {
+                result.fg = cell.fg.or(result.fg);
+            } else {
+                result.ch = cell.ch;
+                result.fg = cell.fg;
+                result.attrs = Attributes::none();
+                result.is_walkable = cell.is_walkable;
+            }
+            result.attrs.extend(cell.attrs);
+            result.bg = cell.bg.or(result.bg);
+        }
+
+        result
+    }
+}
+
+fn is_wide(ch: char) -> bool {
+    ch.width() == Some(2)
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Cell {
     pub ch: char,
     pub fg: Option<Color>,
     pub bg: Option<Color>,
+    pub attrs: Attributes,
     pub is_walkable: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_higher_layer_wins_regardless_of_draw_order() {
+        let mut frame = Frame::new(0, 0, 4, 4);
+
+        frame.set_layer(Layer::Actors);
+        frame.set_char(1, 1, '@');
+        frame.set_layer(Layer::Terrain);
+        frame.set_char(1, 1, '#');
+
+        assert_eq!(frame.buffer[frame.index(1, 1)].ch, '@');
+    }
+
+    #[test]
+    fn test_tint_keeps_glyph_beneath() {
+        let mut frame = Frame::new(0, 0, 4, 4);
+
+        frame.set(2, 2, 'G', Some(Color::Green), None, Attributes::none());
+        frame.set_layer(Layer::Effects);
+        frame.set(2, 2, TRANSPARENT, None, Some(Color::Red), Attributes::none());
+
+        let cell = frame.buffer[frame.index(2, 2)];
+        assert_eq!(cell.ch, 'G');
+        assert_eq!(cell.fg, Some(Color::Green));
+        assert_eq!(cell.bg, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_glyph_without_background_keeps_lower_background() {
+        let mut frame = Frame::new(0, 0, 4, 4);
+
+        frame.set(0, 0, '.', None, Some(Color::DarkBlue), Attributes::none());
+        frame.set_layer(Layer::Actors);
+        frame.set_char(0, 0, '@');
+
+        let cell = frame.buffer[frame.index(0, 0)];
+        assert_eq!(cell.ch, '@');
+        assert_eq!(cell.bg, Some(Color::DarkBlue));
+    }
+
+    #[test]
+    fn test_wide_character_takes_two_columns() {
+        let mut frame = Frame::new(0, 0, 8, 1);
+
+        frame.draw_text(0, 0, "a洞b", None, None, Attributes::none());
+
+        assert_eq!(frame.buffer[frame.index(0, 0)].ch, 'a');
+        assert_eq!(frame.buffer[frame.index(1, 0)].ch, '洞');
+        assert_eq!(frame.buffer[frame.index(2, 0)].ch, CONTINUATION);
+        assert_eq!(frame.buffer[frame.index(3, 0)].ch, 'b');
+        assert_eq!(frame.text_width("a洞b"), 4);
+    }
+
+    #[test]
+    fn test_wide_character_does_not_overflow_row() {
+        let mut frame = Frame::new(0, 0, 3, 1);
+
+        frame.draw_text(0, 0, "ab洞", None, None, Attributes::none());
+
+        assert_eq!(frame.buffer[frame.index(2, 0)].ch, ' ');
+    }
+
+    #[test]
+    fn test_covering_half_of_wide_character_blanks_the_other_half() {
+        let mut frame = Frame::new(0, 0, 4, 1);
+
+        frame.draw_text(0, 0, "洞", None, None, Attributes::none());
+        frame.set_layer(Layer::Actors);
+        frame.set_char(1, 0, '@');
+
+        assert_eq!(frame.buffer[frame.index(0, 0)].ch, ' ');
+        assert_eq!(frame.buffer[frame.index(1, 0)].ch, '@');
+    }
+
+    #[test]
+    fn test_fog_hides_unseen_and_dims_remembered() {
+        let mut frame = Frame::new(0, 0, 3, 1);
+
+        for x in 0..3 {
+            frame.set_char(x, 0, '#');
+        }
+        frame.set_layer(Layer::Actors);
+        frame.set_char(1, 0, 'G');
+        frame.set_char(2, 0, 'G');
+
+        frame.apply_fog(Some(Color::DarkGrey), |x, _| match x {
+            0 => Visibility::Unseen,
+            1 => Visibility::Remembered,
+            _ => Visibility::Visible,
+        });
+
+        assert_eq!(frame.buffer[frame.index(0, 0)].ch, ' ');
+        let remembered = frame.buffer[frame.index(1, 0)];
+        assert_eq!(remembered.ch, '#');
+        assert_eq!(remembered.fg, Some(Color::DarkGrey));
+        assert!(remembered.attrs.has(Attribute::Dim));
+        assert_eq!(frame.buffer[frame.index(2, 0)].ch, 'G');
+    }
+
+    #[test]
+    fn test_zoom_prefers_walls_and_actors() {
+        let mut frame = Frame::new(0, 0, 2, 1);
+        frame.set_zoom(2);
+        let floor = Cell {
+            ch: '.',
+            fg: None,
+            bg: None,
+            attrs: Attributes::none(),
+            is_walkable: true,
+        };
+
+        frame.set_world_cell(
+            0,
+            0,
+            Cell {
+                ch: '#',
+                is_walkable: false,
+                ..floor
+            },
+        );
+        frame.set_world_cell(1, 1, floor);
+        frame.set_world_cell(2, 0, floor);
+        frame.set_layer(Layer::Actors);
+        frame.set_world_cell(3, 1, Cell { ch: 'G', ..floor });
+
+        assert_eq!(frame.buffer[frame.index(0, 0)].ch, '#');
+        assert_eq!(frame.buffer[frame.index(1, 0)].ch, 'G');
+    }
+
+    #[test]
+    fn test_ascii_glyphs_replace_unicode_symbols() {
+        let mut frame = Frame::new(0, 0, 8, 1);
+        frame.set_glyphs(&glyphs::ASCII);
+
+        frame.draw_text(0, 0, "♥♡—洞", None, None, Attributes::none());
+
+        let text: String = frame.buffer.iter().map(|cell| cell.ch).collect();
+        assert_eq!(text, "*o-?    ");
+    }
+}
@@ -1,19 +1,83 @@
 use crate::camera::Camera;
 use crate::drawable::Drawable;
-use crate::frame::Frame;
-use crossterm::event::{poll, read, Event, KeyCode};
-use std::collections::HashMap;
+use crate::frame::{Cell, Frame, Layer};
+use crate::layout::{Constraint, Rect, ScreenLayout};
+use crossterm::event::Event;
+use crossterm::style::{Attribute, Attributes};
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use crate::activity_log::ActivityLog;
 use crate::drawable::fps::Fps;
-use crate::drawable::room::Room;
-use crate::drawable::tree::Tree;
-use crate::enemy::goblin::Goblin;
+use crate::drawable::item::{Item, ItemKind};
+use crate::enemy::goblin::{Goblin, GoblinKind};
+use crate::fov::{self, Visibility};
+use crate::keymap::{self, Command, Keymap};
+use crate::lighting::LightMap;
+use crate::mapgen::{Level, LevelKind, MAX_DEPTH};
 use crate::player::Player;
-use crate::tile::{Coord, Tile};
+use crate::save::{SaveData, SavedGoblin, SavedItem};
+use crate::scheduler::{self, Action, Energy};
+use crate::theme::Role;
+use crate::widget::gauge::Gauge;
+use crate::widget::minimap::{Marker, Minimap};
+use crate::widget::panel::Panel;
+use crate::widget::render;
+use crate::widget::table::Table;
+use crate::tile::{Coord, TileKind, TileMap};
 
-const ALPHABET: &[u8] = b"ABCDEFGHIJKLMN OPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// How the world advances.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    /// Enemies act on timers whether or not the player does anything.
+    RealTime,
+    /// The world only advances when the player spends a turn.
+    TurnBased,
+}
+
+impl GameMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::RealTime => "realtime",
+            GameMode::TurnBased => "turn-based",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "realtime" => Some(GameMode::RealTime),
+            "turn-based" => Some(GameMode::TurnBased),
+            _ => None,
+        }
+    }
+}
+
+/// Smallest terminal the game can be laid out in.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 15;
+
+/// How far the player can see, in tiles.
+const FOV_RADIUS: i32 = 12;
+
+/// Torch fuel burnt by one scheduler tick in turn-based mode, so an ordinary move burns a second.
+const SCHEDULER_TICK: Duration = Duration::from_millis(100);
+
+/// Damage the player's weapon deals.
+const PLAYER_DAMAGE: i32 = 2;
+
+/// Fraction of torch fuel below which the player is warned that it is running out.
+const TORCH_LOW: f32 = 0.2;
+
+/// Largest size of the minimap panel, border included.
+const MINIMAP_WIDTH: u16 = 24;
+const MINIMAP_HEIGHT: u16 = 10;
+
+/// Preferred width of the key bindings help screen.
+const HELP_WIDTH: u16 = 48;
+
+/// The furthest the camera can zoom out, in world tiles per screen cell.
+const MAX_ZOOM: u16 = 8;
 
 const RANDOM_SENTENCES: &[&str] = &[
     "The quick brown fox jumps over the lazy dog.",
@@ -31,165 +95,745 @@ const RANDOM_SENTENCES: &[&str] = &[
 
 ];
 
+/// A level the player is not on, kept as they left it so it is the same when they come back.
+struct Floor {
+    drawables: Vec<Box<dyn Drawable>>,
+    static_map: TileMap,
+    explored: HashSet<Coord>,
+    /// The first level has no way up.
+    stairs_up: Option<Coord>,
+    stairs_down: Coord,
+}
+
+impl Floor {
+    /// Puts stairs on a newly built level at `depth`: down at its exit unless it is the bottom
+    /// level, and up at its start unless it is the first level.
+    fn new(level: Level, depth: u32) -> Self {
+        let mut static_map = level.tiles;
+        let stairs_up = (depth > 1).then_some(level.start);
+        let stairs_down = level.exit;
+
+        if let Some(position) = stairs_up {
+            static_map.set(position, TileKind::StairsUp);
+        }
+        if depth < MAX_DEPTH {
+            static_map.set(stairs_down, TileKind::StairsDown);
+        }
+
+        Floor {
+            drawables: level.drawables,
+            static_map,
+            explored: HashSet::new(),
+            stairs_up,
+            stairs_down,
+        }
+    }
+}
+
 pub struct Game {
     drawables: Vec<Box<dyn Drawable>>,
     pub player: Player,
-    pub request_exit: bool,
+    /// Set when the player asks for the pause menu.
+    pub request_pause: bool,
+    pub mode: GameMode,
+    /// Turns the player has spent, in either mode.
+    pub turns: u32,
+    /// The seed `rng` started from. Starting a game with the same seed replays the same run.
+    pub seed: u64,
+    rng: StdRng,
+    pub level_kind: LevelKind,
+    /// How many levels down the player is, starting at 1.
+    pub depth: u32,
+    /// Every level the player has been to but is not on, by depth.
+    floors: HashMap<u32, Floor>,
+    stairs_up: Option<Coord>,
+    stairs_down: Coord,
+    /// Enemies the player has killed.
+    pub kills: u32,
+    /// What dealt the blow that killed the player, once the run is over.
+    pub killed_by: Option<&'static str>,
     fps: Fps,
     pub camera: Camera,
-    static_map: HashMap<Coord, Tile>,
+    static_map: TileMap,
+    /// Positions in the player's field of view this turn.
+    visible: HashSet<Coord>,
+    /// Every position the player has ever seen.
+    explored: HashSet<Coord>,
     activity_log: ActivityLog,
-    window_resized: bool,
+    show_minimap: bool,
+    pub keymap: Keymap,
+    /// The row selected on the help screen while it is open.
+    help: Option<usize>,
+    /// Whether the camera is zoomed out to fit the whole level.
+    overview: bool,
+    pub layout: ScreenLayout,
+    width: u16,
+    height: u16,
 }
 
 impl Game {
-    pub fn new(view_width: u16, view_height: u16) -> Self {
+    /// A game on a level of `kind` built from `seed`. The levels below are built from the
+    /// same seed as the player gets to them.
+    pub fn with_seed(view_width: u16, view_height: u16, seed: u64, kind: LevelKind) -> Self {
         let camera = Camera::new(0, 0, view_width, view_height);
+        let mut rng = StdRng::seed_from_u64(level_seed(seed, 1));
+        let level = kind.generate(1, &mut rng);
+        let start = level.start;
+        let floor = Floor::new(level, 1);
 
-        let goblin = Goblin::new(20, 10);
-
-        let room = Room {
-            x: 2,
-            y: 2,
-            width: 50,
-            height: 55,
-        };
-        let tree = Tree { x: 15, y: 15 };
-        let tree2 = Tree { x: 16, y: 15 };
-        let tree3 = Tree { x: 17, y: 15 };
-        let tree4 = Tree { x: 18, y: 15 };
-        let tree5 = Tree { x: 18, y: 16 };
-        let tree6 = Tree { x: 18, y: 17 };
-        let tree7 = Tree { x: 18, y: 18 };
         let fps = Fps {
             last_frame: std::time::Instant::now(),
             frames: 0,
             fps: 0,
         };
 
-        let drawables: Vec<Box<dyn Drawable>> = vec![
-            Box::new(room),
-            Box::new(tree),
-            Box::new(tree2),
-            Box::new(tree3),
-            Box::new(tree4),
-            Box::new(tree5),
-            Box::new(tree6),
-            Box::new(tree7),
-            Box::new(goblin),
-        ];
+        let mut camera = camera;
+        camera.bounds = floor.static_map.bounds();
 
-        let mut static_map: HashMap<Coord, Tile> = HashMap::new();
+        let mut game = Self {
+            drawables: floor.drawables,
+            static_map: floor.static_map,
+            visible: HashSet::new(),
+            explored: floor.explored,
+            request_pause: false,
+            mode: GameMode::RealTime,
+            turns: 0,
+            seed,
+            rng,
+            level_kind: kind,
+            depth: 1,
+            floors: HashMap::new(),
+            stairs_up: floor.stairs_up,
+            stairs_down: floor.stairs_down,
+            kills: 0,
+            killed_by: None,
+            fps,
+            camera,
+            player: Player::new(start.0, start.1),
+            activity_log: ActivityLog::new(0, 0, 0, 0),
+            show_minimap: true,
+            keymap: Keymap::default(),
+            help: None,
+            overview: false,
+            layout: ScreenLayout::default(),
+            width: 0,
+            height: 0,
+        };
+        game.resize(view_width, view_height);
+        game.update_fov();
+
+        game
+    }
 
-        for drawables in &drawables {
-            drawables.static_map(&mut static_map);
+    /// Lays the UI out again for a new terminal size. Every panel takes its region from
+    /// `ScreenLayout`, so they keep their proportions of the screen.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+
+        if self.is_too_small() {
+            return;
         }
 
-        Self {
-            drawables,
-            static_map,
-            request_exit: false,
-            fps,
-            camera,
-            player: Player::new(10, 10),
-            activity_log: ActivityLog::new(0, 0, view_width, view_height / 3),
-            window_resized: true,
+        self.layout = ScreenLayout::compute(width, height);
+
+        let log = self.layout.log;
+        self.activity_log.update_dimensions(log.x, log.y, log.width, log.height);
+
+        self.camera.width = self.layout.map.width;
+        self.camera.height = self.layout.map.height;
+        self.fit_overview();
+        self.camera.snap_to((self.player.x, self.player.y));
+    }
+
+    /// Returns whether the terminal is below `MIN_WIDTH` x `MIN_HEIGHT`, in which case only a
+    /// notice is drawn.
+    pub fn is_too_small(&self) -> bool {
+        self.width < MIN_WIDTH || self.height < MIN_HEIGHT
+    }
+
+    fn update_camera(&mut self, dt: Duration) {
+        self.camera.width = self.layout.map.width;
+        self.camera.height = self.layout.map.height;
+        self.fit_overview();
+        self.camera.follow((self.player.x, self.player.y), dt);
+    }
+
+    /// Zooms the camera out just far enough to show the whole level while in overview.
+    fn fit_overview(&mut self) {
+        if let (true, Some(bounds)) = (self.overview, &self.camera.bounds) {
+            self.camera.zoom = self.camera.zoom_to_fit(bounds).min(MAX_ZOOM);
         }
     }
 
-    fn update_camera(&mut self, camera_width: u16, camera_height: u16) {
-        let ui_height = (camera_height as f32 / 3.0).round() as u16;
-        let game_height = camera_height - ui_height;
+    /// Switches between the overview of the whole level and the normal view.
+    fn toggle_overview(&mut self) {
+        self.overview = !self.overview;
+        if !self.overview {
+            self.camera.zoom = 1;
+        }
 
-        self.camera.width = camera_width;
-        self.camera.height = game_height;
+        self.fit_overview();
+        self.camera.snap_to((self.player.x, self.player.y));
+    }
 
-        let half_w = camera_width as i32 / 2;
-        let half_h = game_height as i32 / 2;
+    /// Zooms the camera in or out by `steps` levels, leaving the overview.
+    fn zoom_by(&mut self, steps: i32) {
+        self.overview = false;
+        self.camera.zoom = (self.camera.zoom as i32 + steps).clamp(1, MAX_ZOOM as i32) as u16;
+        self.camera.snap_to((self.player.x, self.player.y));
+    }
 
-        self.camera.x = self.player.x - half_w;
-        self.camera.y = self.player.y - half_h;
+    /// Recomputes what the player can see from their position and adds it to what they have
+    /// explored.
+    fn update_fov(&mut self) {
+        let static_map = &self.static_map;
+        self.visible = fov::compute_fov((self.player.x, self.player.y), FOV_RADIUS, |p| {
+            static_map.is_opaque(p)
+        });
+        self.explored.extend(&self.visible);
+    }
 
-        self.camera.update_bbox();
+    pub fn visibility(&self, position: Coord) -> Visibility {
+        if self.visible.contains(&position) {
+            Visibility::Visible
+        } else if self.explored.contains(&position) {
+            Visibility::Remembered
+        } else {
+            Visibility::Unseen
+        }
+    }
+
+    /// Casts the light of every light source in the level, glowing tiles included, and the
+    /// player's torch.
+    fn light_map(&self) -> LightMap {
+        let mut sources: Vec<_> = self
+            .drawables
+            .iter()
+            .filter_map(|drawable| drawable.light())
+            .chain(self.static_map.lights())
+            .collect();
+        sources.push(self.player.torch.light((self.player.x, self.player.y)));
+
+        LightMap::compute(&sources, |p| self.static_map.is_opaque(p))
     }
 
     pub fn draw(&mut self, frame: &mut Frame) {
         self.fps.update();
         frame.clear();
 
+        if self.is_too_small() {
+            self.draw_too_small(frame);
+            return;
+        }
+
+        frame.set_viewport(self.layout.map);
+
+        frame.set_layer(Layer::Terrain);
+        self.static_map.draw(frame, &self.camera.camera_view);
+
         for drawable in &self.drawables {
             if self.camera.camera_view.intersects(&drawable.bound_box()) {
+                frame.set_layer(drawable.layer());
                 drawable.draw(frame);
             }
         }
 
-        if self.window_resized {
-            let ui_start = frame.height - (frame.height / 3);
+        frame.set_layer(Layer::Ui);
+        self.activity_log.draw(frame);
+
+        frame.set_layer(Layer::Actors);
+        frame.set_world_cell(
+            self.player.x,
+            self.player.y,
+            Cell {
+                ch: '@',
+                fg: frame.color(Role::Player),
+                bg: None,
+                attrs: Attributes::from(Attribute::Bold),
+                is_walkable: true,
+            },
+        );
+
+        let light_map = self.light_map();
+        frame.apply_lighting(|x, y| light_map.light_at((x, y)));
+
+        let remembered = frame.color(Role::Remembered);
+        frame.apply_fog(remembered, |x, y| self.visibility((x, y)));
+    }
+
+    /// Advances the game by one simulation tick of length `dt`, after applying every input
+    /// event received since the previous tick in order.
+    ///
+    /// In turn-based mode nothing else happens until the player acts, and then the world
+    /// advances until the player has the energy to act again. Events after a pause request
+    /// are dropped, and nothing happens at all once the player is dead.
+    pub fn update(&mut self, events: &[Event], dt: Duration) {
+        for event in events {
+            if self.request_pause || self.is_over() {
+                break;
+            }
+
+            if let Some(action) = self.handle_event(event) {
+                self.turns += 1;
+
+                if self.mode == GameMode::TurnBased {
+                    // Wading through water or rubble gives the goblins more time
+                    let times = match action {
+                        Action::Move => self.static_map.move_cost((self.player.x, self.player.y)),
+                        _ => 1,
+                    };
+                    self.player.energy.spend_times(action, times);
+                    self.take_world_turn();
+                }
+            }
+        }
+
+        if self.mode == GameMode::RealTime && !self.is_over() {
+            let mut attackers = Vec::new();
+            for drawable in &mut self.drawables {
+                if let Some(goblin) = drawable.as_any_mut().downcast_mut::<Goblin>() {
+                    let attacks = goblin.update(dt, &self.static_map, &mut self.player);
+                    attackers.extend(std::iter::repeat_n(goblin.kind, attacks));
 
-            self.activity_log.update_dimensions(0, ui_start + 1, 80, frame.height - ui_start - 1);
-            self.window_resized = false;
+                    if !self.player.health.is_alive() {
+                        self.killed_by = Some(goblin.kind.described());
+                        break;
+                    }
+                }
+            }
+
+            self.log_enemy_attacks(&attackers);
+            self.burn_torch(dt);
         }
 
-        self.activity_log.draw(frame);
+        if !self.is_too_small() {
+            self.update_camera(dt);
+        }
+    }
+
+    /// A sentence saying how the run ended and how deep it got, for the game over screen.
+    pub fn cause_of_death(&self) -> String {
+        let depth = self.deepest();
+
+        match self.killed_by {
+            Some(killer) => format!("Killed by {} after reaching depth {}.", killer, depth),
+            None => format!("Died after reaching depth {}.", depth),
+        }
+    }
 
-        if let Some((scr_x, scr_y)) = self.camera.world_to_screen(self.player.x, self.player.y) {
-            frame.set_char(scr_x, scr_y, '@');
+    /// Whether the run has ended with the player's death.
+    pub fn is_over(&self) -> bool {
+        !self.player.health.is_alive()
+    }
+
+    /// The state to write to a save file.
+    pub fn save_data(&self) -> SaveData {
+        SaveData {
+            seed: self.seed,
+            level: self.level_kind.clone(),
+            mode: self.mode,
+            depth: self.depth,
+            deepest: self.deepest(),
+            turns: self.turns,
+            kills: self.kills,
+            player: (self.player.x, self.player.y),
+            health: self.player.health.get_current(),
+            torch: self.player.torch.fuel,
+            potions: self.player.potions,
+            goblins: self
+                .levels()
+                .flat_map(|(depth, drawables)| {
+                    drawables
+                        .iter()
+                        .filter_map(|drawable| drawable.downcast_ref::<Goblin>())
+                        .map(move |goblin| SavedGoblin {
+                            depth,
+                            kind: goblin.kind,
+                            position: (goblin.x, goblin.y),
+                            health: goblin.health.get_current(),
+                        })
+                })
+                .collect(),
+            items: self
+                .levels()
+                .flat_map(|(depth, drawables)| {
+                    drawables
+                        .iter()
+                        .filter_map(|drawable| drawable.downcast_ref::<Item>())
+                        .map(move |item| SavedItem {
+                            depth,
+                            kind: item.kind,
+                            position: (item.x, item.y),
+                            amount: item.amount,
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    /// The deepest level the player has been to.
+    pub fn deepest(&self) -> u32 {
+        self.floors.keys().copied().fold(self.depth, u32::max)
+    }
+
+    /// The drawables of every level the player has been to, by depth.
+    fn levels(&self) -> impl Iterator<Item = (u32, &Vec<Box<dyn Drawable>>)> {
+        std::iter::once((self.depth, &self.drawables)).chain(
+            self.floors
+                .iter()
+                .map(|(&depth, floor)| (depth, &floor.drawables)),
+        )
+    }
+
+    /// Puts the player, goblins and items back where a save left them, in a game started with
+    /// the save's seed. Every level down to the deepest one visited is built again. What the
+    /// player had explored is not saved, so the map starts out unexplored again.
+    pub fn restore(&mut self, save: &SaveData) {
+        self.mode = save.mode;
+        self.turns = save.turns;
+        self.kills = save.kills;
+        self.player.x = save.player.0;
+        self.player.y = save.player.1;
+        self.player.health.set_current(save.health);
+        self.player.torch.fuel = save.torch.clamp(0.0, self.player.torch.max_fuel);
+        self.player.potions = save.potions;
+
+        for depth in 1..=save.deepest {
+            if depth != self.depth && !self.floors.contains_key(&depth) {
+                let floor = self.build_floor(depth);
+                self.floors.insert(depth, floor);
+            }
         }
+
+        let mut levels: Vec<&mut Vec<Box<dyn Drawable>>> = vec![&mut self.drawables];
+        levels.extend(self.floors.values_mut().map(|floor| &mut floor.drawables));
+        for drawables in levels {
+            drawables.retain(|drawable| {
+                drawable.downcast_ref::<Goblin>().is_none()
+                    && drawable.downcast_ref::<Item>().is_none()
+            });
+        }
+
+        for saved in &save.goblins {
+            let (x, y) = saved.position;
+            let mut goblin = Goblin::of_kind(x, y, saved.kind);
+            goblin.health.set_current(saved.health);
+            if let Some(drawables) = self.drawables_at(saved.depth) {
+                drawables.push(Box::new(goblin));
+            }
+        }
+
+        for saved in &save.items {
+            let (x, y) = saved.position;
+            let item = Item::new(x, y, saved.kind, saved.amount);
+            if let Some(drawables) = self.drawables_at(saved.depth) {
+                drawables.push(Box::new(item));
+            }
+        }
+
+        if save.depth != self.depth {
+            self.change_depth(save.depth);
+        }
+        self.player.x = save.player.0;
+        self.player.y = save.player.1;
+
+        self.explored.clear();
+        self.update_fov();
+        self.camera.snap_to((self.player.x, self.player.y));
     }
 
-    pub fn update(&mut self, camera_width: u16, camera_height: u16) {
+    /// The drawables of the level at `depth`, if the player has been there.
+    fn drawables_at(&mut self, depth: u32) -> Option<&mut Vec<Box<dyn Drawable>>> {
+        if depth == self.depth {
+            return Some(&mut self.drawables);
+        }
+
+        self.floors.get_mut(&depth).map(|floor| &mut floor.drawables)
+    }
+
+    /// Builds the level at `depth` for the first time.
+    fn build_floor(&self, depth: u32) -> Floor {
+        let kind = match depth {
+            1 => self.level_kind.clone(),
+            _ => self.level_kind.below(),
+        };
+        let mut rng = StdRng::seed_from_u64(level_seed(self.seed, depth));
+
+        Floor::new(kind.generate(depth, &mut rng), depth)
+    }
+
+    /// Takes the stairs the player is standing on. Returns whether there were any.
+    fn take_stairs(&mut self) -> bool {
+        let here = (self.player.x, self.player.y);
+        let depth = if here == self.stairs_down && self.depth < MAX_DEPTH {
+            self.depth + 1
+        } else if Some(here) == self.stairs_up {
+            self.depth - 1
+        } else {
+            self.activity_log.add_entry("There are no stairs here.");
+            return false;
+        };
+
+        let descending = depth > self.depth;
+        self.change_depth(depth);
+
+        // Arrive on the stairs leading back the way the player came
+        let (x, y) = match descending {
+            true => self.stairs_up.unwrap_or(self.stairs_down),
+            false => self.stairs_down,
+        };
+        self.player.x = x;
+        self.player.y = y;
+        self.update_fov();
+        self.camera.snap_to((x, y));
+
+        let message = match descending {
+            true => format!("You descend to depth {}.", depth),
+            false => format!("You climb back up to depth {}.", depth),
+        };
+        self.activity_log
+            .add_styled_entry(&message, Role::LogInfo, Attributes::from(Attribute::Bold));
+        true
+    }
+
+    /// Leaves the current level as it is and switches to the one at `depth`, building it if
+    /// the player has not been there yet.
+    fn change_depth(&mut self, depth: u32) {
+        let floor = match self.floors.remove(&depth) {
+            Some(floor) => floor,
+            None => self.build_floor(depth),
+        };
+
+        let left = Floor {
+            drawables: std::mem::replace(&mut self.drawables, floor.drawables),
+            static_map: std::mem::replace(&mut self.static_map, floor.static_map),
+            explored: std::mem::replace(&mut self.explored, floor.explored),
+            stairs_up: std::mem::replace(&mut self.stairs_up, floor.stairs_up),
+            stairs_down: std::mem::replace(&mut self.stairs_down, floor.stairs_down),
+        };
+        self.floors.insert(self.depth, left);
+        self.depth = depth;
+
+        self.camera.bounds = self.static_map.bounds();
+        self.fit_overview();
+    }
+
+    /// Says what the player has stepped onto, unless it is plain floor or more of the terrain
+    /// they `left`.
+    fn describe_terrain(&mut self, left: TileKind) {
+        let here = self.static_map.get((self.player.x, self.player.y));
+
+        if here != left && here != TileKind::Floor {
+            self.activity_log.add_entry(here.def().description);
+        }
+    }
+
+    /// Picks up every item where the player stands.
+    fn pick_up_items(&mut self) {
+        let here = (self.player.x, self.player.y);
+        let mut index = 0;
+
+        while index < self.drawables.len() {
+            let Some(item) = self.drawables[index]
+                .downcast_ref::<Item>()
+                .filter(|item| (item.x, item.y) == here)
+            else {
+                index += 1;
+                continue;
+            };
+
+            let message = match item.kind {
+                ItemKind::Potions => {
+                    self.player.potions += item.amount;
+                    match item.amount {
+                        1 => "You pick up a potion.".to_string(),
+                        amount => format!("You pick up {} potions.", amount),
+                    }
+                }
+                ItemKind::Oil => {
+                    self.player.torch.refuel(item.amount as f32);
+                    "You pour a flask of oil on your torch.".to_string()
+                }
+            };
+
+            self.drawables.remove(index);
+            self.activity_log
+                .add_styled_entry(&message, Role::Heal, Attributes::none());
+        }
+    }
+
+    /// The goblins on the current level.
+    fn goblins(&self) -> impl Iterator<Item = &Goblin> {
+        self.drawables
+            .iter()
+            .filter_map(|drawable| drawable.downcast_ref::<Goblin>())
+    }
+
+    /// Lets everything else act in the order the scheduler picks until the player is next.
+    /// Faster goblins get more actions in, and a cheap player action gives them fewer.
+    fn take_world_turn(&mut self) {
+        let mut attackers = Vec::new();
+        let mut ticks = 0;
+
+        loop {
+            let mut goblins: Vec<&mut Goblin> = self
+                .drawables
+                .iter_mut()
+                .filter_map(|drawable| drawable.as_any_mut().downcast_mut::<Goblin>())
+                .collect();
+
+            let mut actors: Vec<&mut Energy> = std::iter::once(&mut self.player.energy)
+                .chain(goblins.iter_mut().map(|goblin| &mut goblin.energy))
+                .collect();
+
+            let Some((index, elapsed)) = scheduler::next_actor(&mut actors) else {
+                break;
+            };
+            ticks += elapsed;
+
+            // The player is always the first actor
+            if index == 0 {
+                break;
+            }
+
+            let goblin = &mut goblins[index - 1];
+            let action = goblin.act(&self.static_map, &mut self.player);
+            goblin.spend(action, &self.static_map);
+            if action == Action::Attack {
+                attackers.push(goblin.kind);
+            }
+
+            // Nobody else gets a turn once the player is dead
+            if !self.player.health.is_alive() {
+                self.killed_by = Some(goblin.kind.described());
+                break;
+            }
+        }
+
+        self.log_enemy_attacks(&attackers);
+        self.burn_torch(SCHEDULER_TICK * ticks);
+    }
+
+    fn log_enemy_attacks(&mut self, attackers: &[GoblinKind]) {
+        for kind in attackers {
+            self.activity_log.add_styled_entry(
+                &format!("The {} hits you.", kind.noun()),
+                Role::LogWarning,
+                Attributes::none(),
+            );
+        }
+    }
+
+    /// Applies one input event and returns the action the player spent energy on, if any.
+    /// Moving, attacking, waiting and drinking a potion are actions; bumping into a wall or
+    /// anything that only changes the view is not.
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        let mut action = None;
         let mut player_dx = 0;
         let mut player_dy = 0;
         let mut damage_goblin = false;
         let mut write_to_log = false;
 
-        // Check to see if the window has been resized
-        if self.camera.height != camera_height || self.camera.width != camera_width {
-            self.window_resized = true;
-        }
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        let command = self.keymap.command(key_event.code);
 
-        if poll(Duration::from_millis(8)).unwrap() {
-            if let Event::Key(key_event) = read().unwrap() {
-                match key_event.code {
-                    KeyCode::Esc => {
-                        self.request_exit = true;
-                    }
-                    KeyCode::Left => {
-                        player_dx = -1;
-                    }
-                    KeyCode::Right => {
-                        player_dx = 1;
-                    }
-                    KeyCode::Up => {
-                        player_dy = -1;
-                    }
-                    KeyCode::Down => {
-                        player_dy = 1;
-                    },
-                    KeyCode::Char('d') => {
-                        damage_goblin = true;
-                    },
-                    KeyCode::Char('t') => {
-                       write_to_log = true;
-                    }
-                    _ => {}
+        // The help screen takes every key while it is open
+        if let Some(selected) = self.help {
+            match command {
+                Some(Command::MoveUp) => self.help = Some(selected.saturating_sub(1)),
+                Some(Command::MoveDown) => {
+                    self.help = Some((selected + 1).min(Command::ALL.len() - 1));
                 }
+                _ => self.help = None,
+            }
+
+            return None;
+        }
+
+        if let Some((dx, dy)) = command.and_then(Command::direction) {
+            if self.camera.free_look {
+                self.camera.pan(dx, dy);
+            } else {
+                player_dx = dx;
+                player_dy = dy;
+            }
+        }
+
+        match command {
+            Some(Command::Pause) if self.camera.free_look => {
+                self.toggle_free_look();
+            }
+            Some(Command::Pause) => {
+                self.request_pause = true;
+            }
+            Some(Command::DebugDamage) => {
+                damage_goblin = true;
+            }
+            Some(Command::DebugLog) => {
+                write_to_log = true;
+            }
+            Some(Command::ToggleMinimap) => {
+                self.show_minimap = !self.show_minimap;
+            }
+            Some(Command::Attack) => {
+                action = self.attack_adjacent().then_some(Action::Attack);
+            }
+            Some(Command::Wait) => {
+                action = Some(Action::Wait);
+            }
+            Some(Command::DrinkPotion) => {
+                action = self.drink_potion();
             }
+            Some(Command::TakeStairs) => {
+                action = self.take_stairs().then_some(Action::Move);
+            }
+            Some(Command::ToggleFreeLook) => {
+                self.toggle_free_look();
+            }
+            Some(Command::ToggleOverview) => {
+                self.toggle_overview();
+            }
+            Some(Command::ZoomIn) => {
+                self.zoom_by(-1);
+            }
+            Some(Command::ZoomOut) => {
+                self.zoom_by(1);
+            }
+            Some(Command::CycleCamera) => {
+                self.camera.mode = self.camera.mode.next();
+                self.activity_log
+                    .add_entry(&format!("Camera mode: {}", self.camera.mode.name()));
+            }
+            Some(Command::Help) => {
+                self.help = Some(0);
+            }
+            _ => {}
         }
 
         if player_dx != 0 || player_dy != 0 {
-            if self
+            let target = (self.player.x + player_dx, self.player.y + player_dy);
+            let left = self.static_map.get((self.player.x, self.player.y));
+
+            // Moving into a goblin attacks it
+            if self.attack_goblin_at(target) {
+                action = Some(Action::Attack);
+            } else if self
                 .player
                 .attempt_move(player_dx, player_dy, &self.static_map)
             {
-                self.player.health.take_damage(1);
+                self.update_fov();
+                self.describe_terrain(left);
+                self.pick_up_items();
+                action = Some(Action::Move);
             }
         }
 
-        for drawable in &mut self.drawables {
-            if let Some(goblin) = drawable.as_any_mut().downcast_mut::<Goblin>() {
-                goblin.update(&self.static_map, &self.player);
-                if damage_goblin {
+        if damage_goblin {
+            for drawable in &mut self.drawables {
+                if let Some(goblin) = drawable.as_any_mut().downcast_mut::<Goblin>() {
                     goblin.health.take_damage(1);
                 }
             }
@@ -197,38 +841,564 @@ impl Game {
 
         if write_to_log {
             // Select a random sentence from the list
-            let sentence = RANDOM_SENTENCES[rand::thread_rng().gen_range(0..RANDOM_SENTENCES.len())];
+            let sentence = RANDOM_SENTENCES[self.rng.gen_range(0..RANDOM_SENTENCES.len())];
 
             self.activity_log.add_entry(sentence);
         }
 
-        self.update_camera(camera_width, camera_height);
+        action
+    }
+
+    fn drink_potion(&mut self) -> Option<Action> {
+        if !self.player.drink_potion() {
+            self.activity_log.add_entry("You have no potions left.");
+            return None;
+        }
+
+        self.activity_log.add_styled_entry(
+            "You drink a potion and feel better.",
+            Role::Heal,
+            Attributes::none(),
+        );
+        Some(Action::DrinkPotion)
+    }
+
+    /// Attacks the first goblin next to the player, diagonals included. Returns whether there
+    /// was one.
+    fn attack_adjacent(&mut self) -> bool {
+        let (x, y) = (self.player.x, self.player.y);
+
+        // The same eight neighbours the player can bump into
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0) && self.attack_goblin_at((x + dx, y + dy)) {
+                    return true;
+                }
+            }
+        }
+
+        self.activity_log.add_entry("There is nothing to attack.");
+        false
+    }
+
+    /// Hits the goblin at `position` with the player's weapon, removing it if it dies. Returns
+    /// whether there was a goblin there.
+    fn attack_goblin_at(&mut self, position: Coord) -> bool {
+        let Some(index) = self.drawables.iter().position(|drawable| {
+            drawable
+                .downcast_ref::<Goblin>()
+                .is_some_and(|goblin| (goblin.x, goblin.y) == position)
+        }) else {
+            return false;
+        };
+
+        let Some(goblin) = self.drawables[index].downcast_mut::<Goblin>() else {
+            return false;
+        };
+        goblin.health.take_damage(PLAYER_DAMAGE);
+        let noun = goblin.kind.noun();
+
+        if goblin.health.get_current() > 0 {
+            self.activity_log.add_entry(&format!("You hit the {}.", noun));
+        } else {
+            self.drawables.remove(index);
+            self.kills += 1;
+            self.activity_log.add_styled_entry(
+                &format!("You hit the {}. It dies.", noun),
+                Role::LogInfo,
+                Attributes::from(Attribute::Bold),
+            );
+        }
+
+        true
+    }
+
+    /// Switches between following the player and panning the camera with the arrow keys.
+    fn toggle_free_look(&mut self) {
+        self.camera.free_look = !self.camera.free_look;
+
+        if self.camera.free_look {
+            self.activity_log
+                .add_entry("Free look: the arrow keys move the camera, f or Esc to return.");
+        } else {
+            self.camera.snap_to((self.player.x, self.player.y));
+        }
+    }
+
+    fn burn_torch(&mut self, dt: Duration) {
+        let before = self.player.torch.fraction();
+        self.player.torch.burn(dt.as_secs_f32());
+        let after = self.player.torch.fraction();
+
+        if after == 0.0 && before > 0.0 {
+            self.activity_log.add_styled_entry(
+                "Your torch sputters and goes out.",
+                Role::LogWarning,
+                Attributes::from(Attribute::Bold),
+            );
+        } else if after <= TORCH_LOW && before > TORCH_LOW {
+            self.activity_log.add_styled_entry(
+                "Your torch is burning low.",
+                Role::LogWarning,
+                Attributes::none(),
+            );
+        }
     }
 
     pub fn draw_ui(&self, frame: &mut Frame) {
-        let ui_start = frame.height - (frame.height / 3);
-        let middle = frame.width / 2;
+        if self.is_too_small() {
+            return;
+        }
+
+        let separator = self.layout.separator;
+        let status = self.layout.status;
 
-        for col in 0..frame.width {
-            frame.set_char(col, ui_start, '—');
+        frame.set_layer(Layer::Ui);
+
+        for col in separator.x..separator.right() {
+            frame.set_char(col, separator.y, frame.glyphs().separator);
         }
 
         frame.draw_text(
-            middle,
-            ui_start + 1,
+            status.x,
+            status.y,
             &format!("Health: {}", self.player.health),
             None,
             None,
+            Attributes::from(Attribute::Bold),
+        );
+
+        let mut health_bar = Gauge::new(
+            self.player.health.get_current(),
+            self.player.health.get_max(),
         );
+        health_bar.filled_color = frame.color(Role::Heal);
+        health_bar.empty_color = frame.color(Role::Damage);
+        render(
+            &health_bar,
+            Rect::new(status.x, status.y + 1, status.width.min(20), 1),
+            frame,
+        );
+
+        frame.draw_text(
+            status.x,
+            status.y + 2,
+            &format!("Depth: {}  Weapon: Rusty Sword", self.depth),
+            None,
+            None,
+            Attributes::none(),
+        );
+
+        frame.draw_text(
+            status.x,
+            status.y + 3,
+            &format!(
+                "Torch: {:.0}%  Potions: {}",
+                self.player.torch.fraction() * 100.0,
+                self.player.potions
+            ),
+            None,
+            None,
+            Attributes::none(),
+        );
+
+        if self.mode == GameMode::TurnBased {
+            frame.draw_text(
+                status.x,
+                status.y + 4,
+                &format!("Turn: {}", self.turns),
+                None,
+                None,
+                Attributes::none(),
+            );
+        }
+
+        if self.show_minimap {
+            self.draw_minimap(frame);
+        }
 
-        frame.draw_text(25, ui_start + 3, "Weapon: Rusty Sword", None, None);
+        if let Some(selected) = self.help {
+            self.draw_help(frame, selected);
+        }
 
+        let fps_text = format!("FPS: {}", self.fps.fps);
         frame.draw_text(
-            frame.width - 10,
-            ui_start + 1,
-            &format!("FPS: {}", self.fps.fps),
+            status.right().saturating_sub(frame.text_width(&fps_text) + 1),
+            status.y,
+            &fps_text,
             None,
             None,
+            Attributes::none(),
+        );
+    }
+
+    /// Draws every command and the keys bound to it in a box over the map.
+    fn draw_help(&self, frame: &mut Frame, selected: usize) {
+        let map = self.layout.map;
+        let width = HELP_WIDTH.min(map.width);
+        let height = (Command::ALL.len() as u16 + 3).min(map.height);
+        let area = Rect::new(
+            map.x + (map.width - width) / 2,
+            map.y + (map.height - height) / 2,
+            width,
+            height,
+        );
+
+        frame.clear_rect(area);
+        let mut panel = Panel::new(&format!("Keys: {}", self.keymap.name));
+        panel.border_color = frame.color(Role::UiAccent);
+        render(&panel, area, frame);
+
+        let mut table = Table::new(
+            vec!["Action".to_string(), "Keys".to_string()],
+            vec![Constraint::Fixed(26), Constraint::Fill],
+        );
+        table.selected = Some(selected);
+        table.rows = Command::ALL
+            .iter()
+            .map(|&command| {
+                let keys: Vec<String> = self
+                    .keymap
+                    .keys(command)
+                    .into_iter()
+                    .map(keymap::key_name)
+                    .collect();
+                vec![command.description().to_string(), keys.join(" ")]
+            })
+            .collect();
+        render(&table, Panel::inner(area), frame);
+    }
+
+    /// Draws the explored level downscaled into the top right corner of the map.
+    fn draw_minimap(&self, frame: &mut Frame) {
+        let map = self.layout.map;
+        let width = MINIMAP_WIDTH.min(map.width / 3);
+        let height = MINIMAP_HEIGHT.min(map.height / 2);
+        let area = Rect::new(map.right() - width, map.y, width, height);
+
+        if area.width < 3 || area.height < 3 {
+            return;
+        }
+
+        let solid: HashSet<Coord> = self
+            .explored
+            .iter()
+            .filter(|&&p| !self.static_map.is_walkable(p))
+            .copied()
+            .collect();
+
+        let mut minimap = Minimap::new(&solid, (self.player.x, self.player.y));
+        minimap.color = frame.color(Role::Wall);
+
+        // Doors and stairs stand out from the walls
+        for (position, kind) in self.static_map.iter() {
+            let is_landmark = matches!(
+                kind,
+                TileKind::Door | TileKind::StairsUp | TileKind::StairsDown
+            );
+            if is_landmark && self.explored.contains(&position) {
+                let def = kind.def();
+                minimap.markers.push(Marker {
+                    position,
+                    ch: def.glyph,
+                    color: frame.color(def.role),
+                });
+            }
+        }
+
+        for goblin in self.goblins() {
+            if self.visible.contains(&(goblin.x, goblin.y)) {
+                minimap.markers.push(Marker {
+                    position: (goblin.x, goblin.y),
+                    ch: 'G',
+                    color: frame.color(Role::Enemy),
+                });
+            }
+        }
+
+        minimap.markers.push(Marker {
+            position: (self.player.x, self.player.y),
+            ch: '@',
+            color: frame.color(Role::Player),
+        });
+
+        frame.clear_rect(area);
+        render(&Panel::new("Map"), area, frame);
+        render(&minimap, Panel::inner(area), frame);
+    }
+
+    fn draw_too_small(&self, frame: &mut Frame) {
+        let lines = [
+            "Terminal too small".to_string(),
+            format!("need at least {}x{}", MIN_WIDTH, MIN_HEIGHT),
+            format!("currently {}x{}", self.width, self.height),
+        ];
+
+        frame.set_layer(Layer::Ui);
+
+        let top = (frame.height / 2).saturating_sub(1);
+        for (i, line) in lines.iter().enumerate() {
+            let x = frame.width.saturating_sub(frame.text_width(line)) / 2;
+            frame.draw_text(x, top + i as u16, line, None, None, Attributes::none());
+        }
+    }
+}
+
+/// The seed the level at `depth` is built from. The first level uses the game's own seed.
+fn level_seed(seed: u64, depth: u32) -> u64 {
+    seed ^ (depth as u64 - 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+
+    /// A game on the hand-made demo level, which is the same whatever the seed.
+    fn demo(view_width: u16, view_height: u16) -> Game {
+        Game::with_seed(view_width, view_height, 0, LevelKind::Demo)
+    }
+
+    /// Draws the whole screen the way the app does and dumps it as text.
+    fn screen(game: &mut Game) -> String {
+        game.update(&[], Duration::ZERO);
+
+        let mut frame = Frame::new(game.camera.x, game.camera.y, game.width, game.height);
+        frame.set_zoom(game.camera.zoom);
+        game.draw(&mut frame);
+        game.draw_ui(&mut frame);
+
+        frame_to_text(&frame)
+    }
+
+    #[test]
+    fn test_goblins_stop_once_the_player_is_dead() {
+        let mut game = demo(60, 21);
+        game.mode = GameMode::TurnBased;
+        game.drawables
+            .retain(|drawable| drawable.downcast_ref::<Goblin>().is_none());
+
+        let (x, y) = (game.player.x, game.player.y);
+        game.drawables
+            .push(Box::new(Goblin::of_kind(x - 1, y, GoblinKind::Brute)));
+        game.drawables
+            .push(Box::new(Goblin::of_kind(x + 1, y, GoblinKind::Scout)));
+        game.player.health.set_current(1);
+        game.player.energy.energy = 0;
+
+        game.take_world_turn();
+
+        // The brute's hit is the one that killed, and the scout never got to swing
+        assert!(game.is_over());
+        assert_eq!(game.killed_by, Some(GoblinKind::Brute.described()));
+        assert!(game.goblins().nth(1).unwrap().energy.can_act());
+    }
+
+    #[test]
+    fn test_stairs_lead_down_and_back_to_the_same_level() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(60, 21);
+        let key = |code| Event::Key(KeyEvent::from(code));
+
+        // Hurt the demo goblin, then walk onto the stairs down at the level's exit
+        game.update(&[key(KeyCode::Char('d'))], Duration::ZERO);
+        (game.player.x, game.player.y) = (45, 50);
+        game.update(&[key(KeyCode::Char('>'))], Duration::ZERO);
+
+        assert_eq!(game.depth, 2);
+        assert_eq!(game.deepest(), 2);
+        let arrival = (game.player.x, game.player.y);
+        let goblins: Vec<_> = game.goblins().map(|goblin| (goblin.x, goblin.y)).collect();
+
+        game.update(&[key(KeyCode::Char('<'))], Duration::ZERO);
+        assert_eq!(game.depth, 1);
+        assert_eq!((game.player.x, game.player.y), (45, 50));
+        assert_eq!(game.goblins().next().unwrap().health.get_current(), 9);
+
+        game.update(&[key(KeyCode::Char('>'))], Duration::ZERO);
+        assert_eq!((game.player.x, game.player.y), arrival);
+        assert_eq!(
+            game.goblins().map(|goblin| (goblin.x, goblin.y)).collect::<Vec<_>>(),
+            goblins
         );
     }
+
+    #[test]
+    fn test_restoring_a_save_returns_to_its_depth() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(60, 21);
+        (game.player.x, game.player.y) = (45, 50);
+        game.update(&[Event::Key(KeyEvent::from(KeyCode::Char('>')))], Duration::ZERO);
+        let save = game.save_data();
+
+        let mut restored = Game::with_seed(60, 21, save.seed, save.level.clone());
+        restored.restore(&save);
+
+        assert_eq!(restored.depth, 2);
+        assert_eq!((restored.player.x, restored.player.y), (game.player.x, game.player.y));
+        assert_eq!(restored.goblins().count(), game.goblins().count());
+        assert_eq!(restored.save_data(), save);
+    }
+
+    #[test]
+    fn test_cause_of_death_tells_the_deepest_level_reached() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(60, 21);
+        let key = |code| Event::Key(KeyEvent::from(code));
+
+        (game.player.x, game.player.y) = (45, 50);
+        game.update(&[key(KeyCode::Char('>'))], Duration::ZERO);
+        game.update(&[key(KeyCode::Char('<'))], Duration::ZERO);
+        game.player.health.set_current(0);
+
+        assert_eq!(game.depth, 1);
+        assert_eq!(game.cause_of_death(), "Died after reaching depth 2.");
+    }
+
+    #[test]
+    fn test_items_at_the_edge_of_the_view_are_drawn() {
+
+        let mut game = demo(60, 21);
+        let position = (game.camera.camera_view.left, game.camera.camera_view.top);
+        game.static_map.set(position, TileKind::Floor);
+        game.visible.insert(position);
+        game.drawables
+            .push(Box::new(Item::new(position.0, position.1, ItemKind::Potions, 1)));
+
+        let mut frame = Frame::new(position.0, position.1, 60, 21);
+        game.draw(&mut frame);
+
+        let map = game.layout.map;
+        let row = frame_to_text(&frame).lines().nth(map.y as usize).unwrap().to_string();
+        assert_eq!(row.chars().nth(map.x as usize), Some('!'));
+    }
+
+    #[test]
+    fn test_attacks_reach_diagonally() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(60, 21);
+        game.mode = GameMode::TurnBased;
+        game.drawables
+            .retain(|drawable| drawable.downcast_ref::<Goblin>().is_none());
+
+        let (x, y) = (game.player.x, game.player.y);
+        game.drawables
+            .push(Box::new(Goblin::of_kind(x + 1, y + 1, GoblinKind::Brute)));
+
+        let attack = Event::Key(KeyEvent::from(KeyCode::Char('a')));
+        game.update(&[attack], Duration::ZERO);
+
+        let goblin = game.goblins().next().unwrap();
+        assert!(goblin.health.get_current() < goblin.health.get_max());
+        // The goblin answered from the same corner instead of stepping around first
+        assert_eq!((goblin.x, goblin.y), (x + 1, y + 1));
+        assert!(game.player.health.get_current() < game.player.health.get_max());
+    }
+
+    #[test]
+    fn test_stepping_onto_terrain_describes_it() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(60, 21);
+        let (x, y) = (game.player.x, game.player.y);
+        game.static_map.set((x + 1, y), TileKind::Water);
+        game.static_map.set((x + 2, y), TileKind::Water);
+
+        let right = Event::Key(KeyEvent::from(KeyCode::Right));
+        game.update(&[right.clone(), right], Duration::ZERO);
+
+        let mut frame = Frame::new(0, 0, 60, 21);
+        game.draw(&mut frame);
+        // Wading on through the same water does not repeat the description
+        assert_eq!(game.player.x, x + 2);
+        assert_eq!(frame_to_text(&frame).matches("Knee-deep water").count(), 1);
+    }
+
+    #[test]
+    fn test_tiny_terminal_shows_notice() {
+        let mut game = demo(60, 21);
+
+        game.resize(20, 4);
+
+        let text = screen(&mut game);
+        assert!(text.contains("Terminal too small"));
+        assert!(text.contains("need at least 40x15"));
+    }
+
+    #[test]
+    fn test_layout_follows_resize() {
+        let mut game = demo(60, 21);
+
+        game.resize(100, 30);
+
+        let text = screen(&mut game);
+        let lines: Vec<&str> = text.split('\n').collect();
+
+        assert_eq!(lines[10].chars().nth(50), Some('@'));
+        assert!(lines[21].contains("Health: 100/100"));
+        assert!(lines[21].ends_with("FPS: 0"));
+    }
+
+    #[test]
+    fn test_overview_zooms_out_to_the_whole_level() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(60, 21);
+
+        let toggle = Event::Key(KeyEvent::from(KeyCode::Char('z')));
+        game.update(std::slice::from_ref(&toggle), Duration::ZERO);
+
+        // The 50x55 room needs four tiles per cell to fit the 60x14 map
+        assert_eq!(game.camera.zoom, 4);
+        assert!(screen(&mut game).contains('@'));
+
+        game.update(&[toggle], Duration::ZERO);
+        assert_eq!(game.camera.zoom, 1);
+    }
+
+    #[test]
+    fn test_turn_based_world_waits_for_the_player() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(60, 21);
+        game.mode = GameMode::TurnBased;
+        let start = game.goblins().next().unwrap().x;
+
+        for _ in 0..10 {
+            game.update(&[], Duration::from_secs(1));
+        }
+        assert_eq!(game.goblins().next().unwrap().x, start);
+
+        let wait = Event::Key(KeyEvent::from(KeyCode::Char('.')));
+        game.update(std::slice::from_ref(&wait), Duration::ZERO);
+        assert_eq!(game.turns, 1);
+        assert_eq!(game.goblins().next().unwrap().x, start - 1);
+
+        // Waiting costs half a move, so the player gets the next turn before the goblin is ready
+        game.update(&[wait], Duration::ZERO);
+        assert_eq!(game.turns, 2);
+        assert_eq!(game.goblins().next().unwrap().x, start - 1);
+    }
+
+    #[test]
+    fn test_help_screen_lists_bindings_and_swallows_keys() {
+        use crossterm::event::{KeyCode, KeyEvent};
+
+        let mut game = demo(80, 30);
+        let (x, y) = (game.player.x, game.player.y);
+        let key = |code| Event::Key(KeyEvent::from(code));
+
+        game.update(&[key(KeyCode::Char('?'))], Duration::ZERO);
+        let text = screen(&mut game);
+        assert!(text.contains("Keys: arrows"));
+        assert!(text.contains("Move up and left          home"));
+
+        // The first key closes the help screen without moving
+        game.update(&[key(KeyCode::Home), key(KeyCode::Home)], Duration::ZERO);
+        assert_eq!((game.player.x, game.player.y), (x - 1, y - 1));
+    }
 }
@@ -0,0 +1,88 @@
+/// GlyphSet names the symbols the game draws so terminals that render non-ASCII characters badly
+/// can swap them for plain ASCII.
+pub struct GlyphSet {
+    pub heart_full: char,
+    pub heart_empty: char,
+    pub separator: char,
+    pub path: char,
+    pub border_horizontal: char,
+    pub border_vertical: char,
+    pub border_top_left: char,
+    pub border_top_right: char,
+    pub border_bottom_left: char,
+    pub border_bottom_right: char,
+    pub gauge_filled: char,
+    pub gauge_empty: char,
+    pub scroll_up: char,
+    pub scroll_down: char,
+    pub sconce: char,
+    pub fungus: char,
+    /// When set, every non-ASCII character drawn to a frame is replaced with `ascii_fallback`.
+    pub ascii_only: bool,
+}
+
+pub const UNICODE: GlyphSet = GlyphSet {
+    heart_full: '♥',
+    heart_empty: '♡',
+    separator: '—',
+    path: '·',
+    border_horizontal: '─',
+    border_vertical: '│',
+    border_top_left: '┌',
+    border_top_right: '┐',
+    border_bottom_left: '└',
+    border_bottom_right: '┘',
+    gauge_filled: '█',
+    gauge_empty: '░',
+    scroll_up: '▲',
+    scroll_down: '▼',
+    sconce: 'Ψ',
+    fungus: '♣',
+    ascii_only: false,
+};
+
+pub const ASCII: GlyphSet = GlyphSet {
+    heart_full: '*',
+    heart_empty: 'o',
+    separator: '-',
+    path: '.',
+    border_horizontal: '-',
+    border_vertical: '|',
+    border_top_left: '+',
+    border_top_right: '+',
+    border_bottom_left: '+',
+    border_bottom_right: '+',
+    gauge_filled: '#',
+    gauge_empty: '-',
+    scroll_up: '^',
+    scroll_down: 'v',
+    sconce: '!',
+    fungus: '"',
+    ascii_only: true,
+};
+
+/// Returns the closest ASCII look-alike for a character, or `?` when there is none.
+pub fn ascii_fallback(ch: char) -> char {
+    if ch.is_ascii() {
+        return ch;
+    }
+
+    match ch {
+        '♥' | '❤' | '★' | '✱' => '*',
+        '♡' | '○' | '●' | '◦' => 'o',
+        '—' | '–' | '─' | '━' => '-',
+        '│' | '┃' | '║' => '|',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => '+',
+        '·' | '•' | '∙' => '.',
+        '█' | '▓' | '▒' => '#',
+        '░' => '-',
+        '▲' => '^',
+        '▼' => 'v',
+        '‘' | '’' => '\'',
+        '“' | '”' => '"',
+        '…' | '≈' | '≋' => '~',
+        'Ψ' => '!',
+        '♣' => '"',
+        _ => '?',
+    }
+}
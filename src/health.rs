@@ -23,6 +23,11 @@ impl Health {
         }
     }
 
+    /// Sets the current health, kept between zero and the maximum.
+    pub fn set_current(&mut self, current: i32) {
+        self.current = current.clamp(0, self.max);
+    }
+
     pub fn get_current(&self) -> i32 {
         self.current
     }
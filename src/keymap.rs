@@ -0,0 +1,535 @@
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Something the player can do with a key press.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Command {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    MoveUpLeft,
+    MoveUpRight,
+    MoveDownLeft,
+    MoveDownRight,
+    Wait,
+    Attack,
+    DrinkPotion,
+    TakeStairs,
+    ToggleMinimap,
+    ToggleFreeLook,
+    ToggleOverview,
+    ZoomIn,
+    ZoomOut,
+    CycleCamera,
+    Help,
+    Pause,
+    DebugDamage,
+    DebugLog,
+}
+
+impl Command {
+    /// Every command, in the order the help screen lists them.
+    pub const ALL: [Command; 22] = [
+        Command::MoveLeft,
+        Command::MoveRight,
+        Command::MoveUp,
+        Command::MoveDown,
+        Command::MoveUpLeft,
+        Command::MoveUpRight,
+        Command::MoveDownLeft,
+        Command::MoveDownRight,
+        Command::Wait,
+        Command::Attack,
+        Command::DrinkPotion,
+        Command::TakeStairs,
+        Command::ToggleMinimap,
+        Command::ToggleFreeLook,
+        Command::ToggleOverview,
+        Command::ZoomIn,
+        Command::ZoomOut,
+        Command::CycleCamera,
+        Command::Help,
+        Command::Pause,
+        Command::DebugDamage,
+        Command::DebugLog,
+    ];
+
+    /// The name used for the command in keymap files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Command::MoveLeft => "move-left",
+            Command::MoveRight => "move-right",
+            Command::MoveUp => "move-up",
+            Command::MoveDown => "move-down",
+            Command::MoveUpLeft => "move-up-left",
+            Command::MoveUpRight => "move-up-right",
+            Command::MoveDownLeft => "move-down-left",
+            Command::MoveDownRight => "move-down-right",
+            Command::Wait => "wait",
+            Command::Attack => "attack",
+            Command::DrinkPotion => "drink-potion",
+            Command::TakeStairs => "stairs",
+            Command::ToggleMinimap => "minimap",
+            Command::ToggleFreeLook => "free-look",
+            Command::ToggleOverview => "overview",
+            Command::ZoomIn => "zoom-in",
+            Command::ZoomOut => "zoom-out",
+            Command::CycleCamera => "cycle-camera",
+            Command::Help => "help",
+            Command::Pause => "pause",
+            Command::DebugDamage => "debug-damage",
+            Command::DebugLog => "debug-log",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Command> {
+        Command::ALL
+            .iter()
+            .copied()
+            .find(|command| command.name() == name)
+    }
+
+    /// What the command does, as shown on the help screen.
+    pub fn description(self) -> &'static str {
+        match self {
+            Command::MoveLeft => "Move left",
+            Command::MoveRight => "Move right",
+            Command::MoveUp => "Move up",
+            Command::MoveDown => "Move down",
+            Command::MoveUpLeft => "Move up and left",
+            Command::MoveUpRight => "Move up and right",
+            Command::MoveDownLeft => "Move down and left",
+            Command::MoveDownRight => "Move down and right",
+            Command::Wait => "Wait a moment",
+            Command::Attack => "Attack an adjacent enemy",
+            Command::DrinkPotion => "Drink a potion",
+            Command::TakeStairs => "Take the stairs up or down",
+            Command::ToggleMinimap => "Show or hide the minimap",
+            Command::ToggleFreeLook => "Look around freely",
+            Command::ToggleOverview => "Show the whole level",
+            Command::ZoomIn => "Zoom in",
+            Command::ZoomOut => "Zoom out",
+            Command::CycleCamera => "Change camera mode",
+            Command::Help => "Show these key bindings",
+            Command::Pause => "Pause the game",
+            Command::DebugDamage => "Hurt every goblin (debug)",
+            Command::DebugLog => "Write to the log (debug)",
+        }
+    }
+
+    /// The step a movement command takes, or `None` for anything else.
+    pub fn direction(self) -> Option<(i32, i32)> {
+        match self {
+            Command::MoveLeft => Some((-1, 0)),
+            Command::MoveRight => Some((1, 0)),
+            Command::MoveUp => Some((0, -1)),
+            Command::MoveDown => Some((0, 1)),
+            Command::MoveUpLeft => Some((-1, -1)),
+            Command::MoveUpRight => Some((1, -1)),
+            Command::MoveDownLeft => Some((-1, 1)),
+            Command::MoveDownRight => Some((1, 1)),
+            _ => None,
+        }
+    }
+}
+
+/// Keymap maps key presses to commands. A key runs at most one command, while a command can
+/// have any number of keys.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    pub name: String,
+    bindings: HashMap<KeyCode, Command>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::arrows()
+    }
+}
+
+impl Keymap {
+    pub const PRESETS: [&'static str; 4] = ["arrows", "numpad", "vi", "wasd"];
+
+    /// The bindings every preset starts from: arrow keys to move and a letter for everything
+    /// else.
+    fn common(name: &str) -> Self {
+        let mut keymap = Keymap {
+            name: name.to_string(),
+            bindings: HashMap::new(),
+        };
+
+        keymap.bind_all(&[
+            (KeyCode::Left, Command::MoveLeft),
+            (KeyCode::Right, Command::MoveRight),
+            (KeyCode::Up, Command::MoveUp),
+            (KeyCode::Down, Command::MoveDown),
+            (KeyCode::Char('.'), Command::Wait),
+            (KeyCode::Char(' '), Command::Wait),
+            (KeyCode::Char('a'), Command::Attack),
+            (KeyCode::Char('q'), Command::DrinkPotion),
+            (KeyCode::Char('>'), Command::TakeStairs),
+            (KeyCode::Char('<'), Command::TakeStairs),
+            (KeyCode::Char('m'), Command::ToggleMinimap),
+            (KeyCode::Char('f'), Command::ToggleFreeLook),
+            (KeyCode::Char('z'), Command::ToggleOverview),
+            (KeyCode::Char('+'), Command::ZoomIn),
+            (KeyCode::Char('='), Command::ZoomIn),
+            (KeyCode::Char('-'), Command::ZoomOut),
+            (KeyCode::Char('c'), Command::CycleCamera),
+            (KeyCode::Char('?'), Command::Help),
+            (KeyCode::Esc, Command::Pause),
+            (KeyCode::Char('d'), Command::DebugDamage),
+            (KeyCode::Char('t'), Command::DebugLog),
+        ]);
+
+        keymap
+    }
+
+    /// Arrow keys, with Home, Page Up, End and Page Down for the diagonals.
+    pub fn arrows() -> Self {
+        let mut keymap = Keymap::common("arrows");
+        keymap.bind_all(&[
+            (KeyCode::Home, Command::MoveUpLeft),
+            (KeyCode::PageUp, Command::MoveUpRight),
+            (KeyCode::End, Command::MoveDownLeft),
+            (KeyCode::PageDown, Command::MoveDownRight),
+        ]);
+        keymap
+    }
+
+    /// The number pad, whether Num Lock is on or off, with 5 to wait.
+    pub fn numpad() -> Self {
+        let mut keymap = Keymap::arrows();
+        keymap.name = "numpad".to_string();
+        keymap.bind_all(&[
+            (KeyCode::Char('4'), Command::MoveLeft),
+            (KeyCode::Char('6'), Command::MoveRight),
+            (KeyCode::Char('8'), Command::MoveUp),
+            (KeyCode::Char('2'), Command::MoveDown),
+            (KeyCode::Char('7'), Command::MoveUpLeft),
+            (KeyCode::Char('9'), Command::MoveUpRight),
+            (KeyCode::Char('1'), Command::MoveDownLeft),
+            (KeyCode::Char('3'), Command::MoveDownRight),
+            (KeyCode::Char('5'), Command::Wait),
+            (KeyCode::KeypadBegin, Command::Wait),
+        ]);
+        keymap
+    }
+
+    /// The vi keys `hjkl` and `yubn` for the diagonals.
+    pub fn vi() -> Self {
+        let mut keymap = Keymap::common("vi");
+        keymap.bind_all(&[
+            (KeyCode::Char('h'), Command::MoveLeft),
+            (KeyCode::Char('l'), Command::MoveRight),
+            (KeyCode::Char('k'), Command::MoveUp),
+            (KeyCode::Char('j'), Command::MoveDown),
+            (KeyCode::Char('y'), Command::MoveUpLeft),
+            (KeyCode::Char('u'), Command::MoveUpRight),
+            (KeyCode::Char('b'), Command::MoveDownLeft),
+            (KeyCode::Char('n'), Command::MoveDownRight),
+        ]);
+        keymap
+    }
+
+    /// `wasd` with `qezc` for the diagonals. The commands those letters usually run move to
+    /// other keys.
+    pub fn wasd() -> Self {
+        let mut keymap = Keymap::common("wasd");
+        keymap.bind_all(&[
+            (KeyCode::Char('a'), Command::MoveLeft),
+            (KeyCode::Char('d'), Command::MoveRight),
+            (KeyCode::Char('w'), Command::MoveUp),
+            (KeyCode::Char('s'), Command::MoveDown),
+            (KeyCode::Char('q'), Command::MoveUpLeft),
+            (KeyCode::Char('e'), Command::MoveUpRight),
+            (KeyCode::Char('z'), Command::MoveDownLeft),
+            (KeyCode::Char('c'), Command::MoveDownRight),
+            (KeyCode::Char('r'), Command::Attack),
+            (KeyCode::Char('p'), Command::DrinkPotion),
+            (KeyCode::Char('o'), Command::ToggleOverview),
+            (KeyCode::Char('v'), Command::CycleCamera),
+            (KeyCode::Char('g'), Command::DebugDamage),
+        ]);
+        keymap
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "arrows" => Some(Keymap::arrows()),
+            "numpad" => Some(Keymap::numpad()),
+            "vi" => Some(Keymap::vi()),
+            "wasd" => Some(Keymap::wasd()),
+            _ => None,
+        }
+    }
+
+    /// Returns the preset called `spec`, or loads it from a file otherwise.
+    pub fn resolve(spec: &str) -> Result<Self, KeymapError> {
+        match Keymap::preset(spec) {
+            Some(keymap) => Ok(keymap),
+            None => Keymap::load(spec),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeymapError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(KeymapError::Io)?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Keymap::parse(&name, &text)
+    }
+
+    /// Parses a keymap file. Each non-empty line is `command = key key ...`, replacing the keys
+    /// the command had, and `none` leaves it unbound. `#` starts a comment, and an optional
+    /// `preset = <name>` line picks the preset that unlisted commands keep their keys from.
+    pub fn parse(name: &str, text: &str) -> Result<Self, KeymapError> {
+        let mut keymap = Keymap::default();
+        let mut assignments = Vec::new();
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(KeymapError::parse(line_number, "expected `command = keys`"));
+            };
+            let key = key.trim();
+            let values: Vec<&str> = value
+                .split_whitespace()
+                .take_while(|value| !value.starts_with('#'))
+                .collect();
+
+            if key == "preset" {
+                let preset = values.join(" ");
+                keymap = Keymap::preset(&preset).ok_or_else(|| {
+                    KeymapError::parse(line_number, format!("unknown preset `{}`", preset))
+                })?;
+                continue;
+            }
+
+            let command = Command::from_name(key).ok_or_else(|| {
+                KeymapError::parse(line_number, format!("unknown command `{}`", key))
+            })?;
+
+            if values.is_empty() {
+                return Err(KeymapError::parse(
+                    line_number,
+                    format!("no keys for `{}`, use `none` to unbind it", key),
+                ));
+            }
+
+            let mut keys = Vec::new();
+            for value in values.into_iter().filter(|&value| value != "none") {
+                let key = parse_key(value).ok_or_else(|| {
+                    KeymapError::parse(line_number, format!("unknown key `{}`", value))
+                })?;
+                keys.push(key);
+            }
+
+            assignments.push((command, keys));
+        }
+
+        for (command, keys) in assignments {
+            keymap.bindings.retain(|_, bound| *bound != command);
+            for key in keys {
+                keymap.bind(key, command);
+            }
+        }
+        keymap.name = name.to_string();
+
+        Ok(keymap)
+    }
+
+    pub fn command(&self, key: KeyCode) -> Option<Command> {
+        self.bindings.get(&key).copied()
+    }
+
+    /// Binds `key` to `command`, taking it away from whatever it ran before.
+    pub fn bind(&mut self, key: KeyCode, command: Command) {
+        self.bindings.insert(key, command);
+    }
+
+    fn bind_all(&mut self, bindings: &[(KeyCode, Command)]) {
+        for &(key, command) in bindings {
+            self.bind(key, command);
+        }
+    }
+
+    /// The keys bound to `command`, sorted by name.
+    pub fn keys(&self, command: Command) -> Vec<KeyCode> {
+        let mut keys: Vec<KeyCode> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound)| **bound == command)
+            .map(|(key, _)| *key)
+            .collect();
+        keys.sort_by_key(|key| (key_name(*key).chars().count() > 1, key_name(*key)));
+        keys
+    }
+}
+
+#[derive(Debug)]
+pub enum KeymapError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl KeymapError {
+    fn parse(line: usize, message: impl Into<String>) -> Self {
+        KeymapError::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeymapError::Io(err) => write!(f, "could not read keymap: {}", err),
+            KeymapError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+/// Parses a key: a single character stands for itself, and anything longer names a key such
+/// as `left`, `page-up`, `space`, `esc` or `f1`.
+pub fn parse_key(value: &str) -> Option<KeyCode> {
+    let mut chars = value.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+
+    let name = value.to_lowercase().replace(['_', '-'], "");
+
+    if let Some(number) = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=12).contains(&number).then_some(KeyCode::F(number));
+    }
+
+    let key = match name.as_str() {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "insert" => KeyCode::Insert,
+        "delete" => KeyCode::Delete,
+        "begin" => KeyCode::KeypadBegin,
+        "space" => KeyCode::Char(' '),
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "esc" | "escape" => KeyCode::Esc,
+        _ => return None,
+    };
+
+    Some(key)
+}
+
+/// The name of a key as `parse_key` accepts it.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "page-up".to_string(),
+        KeyCode::PageDown => "page-down".to_string(),
+        KeyCode::Insert => "insert".to_string(),
+        KeyCode::Delete => "delete".to_string(),
+        KeyCode::KeypadBegin => "begin".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::F(number) => format!("f{}", number),
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_bind_every_command() {
+        for name in Keymap::PRESETS {
+            let keymap = Keymap::preset(name).unwrap();
+
+            for command in Command::ALL {
+                assert!(
+                    !keymap.keys(command).is_empty(),
+                    "{} has no key for {}",
+                    name,
+                    command.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_replaces_keys_of_listed_commands() {
+        let text = "preset = vi\n\
+                    wait = 5 space # rest\n\
+                    debug-damage = none\n";
+        let keymap = Keymap::parse("mine", text).unwrap();
+
+        assert_eq!(keymap.name, "mine");
+        assert_eq!(keymap.command(KeyCode::Char('h')), Some(Command::MoveLeft));
+        assert_eq!(keymap.command(KeyCode::Char('.')), None);
+        assert_eq!(
+            keymap.keys(Command::Wait),
+            [KeyCode::Char('5'), KeyCode::Char(' ')]
+        );
+        assert_eq!(keymap.command(KeyCode::Char('d')), None);
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = Keymap::parse("bad", "wait = .\n\njump = j\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 3: unknown command `jump`");
+
+        let err = Keymap::parse("bad", "wait = page-sideways\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: unknown key `page-sideways`");
+    }
+
+    #[test]
+    fn test_example_keymap_parses() {
+        let keymap = Keymap::parse("laptop", include_str!("../keymaps/laptop.keys")).unwrap();
+
+        assert_eq!(keymap.command(KeyCode::Char('k')), Some(Command::MoveUp));
+        assert_eq!(keymap.command(KeyCode::Home), Some(Command::MoveUpLeft));
+        assert_eq!(keymap.keys(Command::DebugLog), []);
+    }
+
+    #[test]
+    fn test_key_names_round_trip() {
+        for key in [
+            KeyCode::Char('x'),
+            KeyCode::Char(' '),
+            KeyCode::PageDown,
+            KeyCode::KeypadBegin,
+            KeyCode::F(5),
+        ] {
+            assert_eq!(parse_key(&key_name(key)), Some(key));
+        }
+    }
+}
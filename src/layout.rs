@@ -0,0 +1,243 @@
+/// A rectangle of screen cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The column just past the right edge.
+    pub fn right(&self) -> u16 {
+        self.x + self.width
+    }
+
+    /// The row just past the bottom edge.
+    pub fn bottom(&self) -> u16 {
+        self.y + self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Shrinks the rectangle by `margin` cells on every side.
+    pub fn inner(&self, margin: u16) -> Rect {
+        Rect {
+            x: self.x + margin,
+            y: self.y + margin,
+            width: self.width.saturating_sub(margin * 2),
+            height: self.height.saturating_sub(margin * 2),
+        }
+    }
+
+    /// Returns the overlap of two rectangles, which is empty when they do not touch.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        Rect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
+
+/// How much space a region of a `Layout` takes along the split direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Exactly this many cells, or whatever is left if there is less.
+    Fixed(u16),
+    /// A percentage of the whole area, rounded down.
+    Percentage(u16),
+    /// An equal share of the space the other constraints leave over.
+    Fill,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// Layout splits a rectangle into a row or column of regions.
+pub struct Layout {
+    direction: Direction,
+    constraints: Vec<Constraint>,
+}
+
+impl Layout {
+    /// Stacks regions top to bottom.
+    pub fn vertical(constraints: impl Into<Vec<Constraint>>) -> Self {
+        Layout {
+            direction: Direction::Vertical,
+            constraints: constraints.into(),
+        }
+    }
+
+    /// Places regions left to right.
+    pub fn horizontal(constraints: impl Into<Vec<Constraint>>) -> Self {
+        Layout {
+            direction: Direction::Horizontal,
+            constraints: constraints.into(),
+        }
+    }
+
+    /// Returns one rectangle per constraint. Fixed and percentage regions are sized first, in
+    /// order, then the remainder is shared between the fill regions with any leftover cell going
+    /// to the last one.
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let total = match self.direction {
+            Direction::Horizontal => area.width,
+            Direction::Vertical => area.height,
+        };
+
+        let mut sizes = vec![0u16; self.constraints.len()];
+        let mut remaining = total;
+
+        for (size, constraint) in sizes.iter_mut().zip(&self.constraints) {
+            let wanted = match constraint {
+                Constraint::Fixed(cells) => *cells,
+                Constraint::Percentage(percent) => {
+                    (total as u32 * (*percent).min(100) as u32 / 100) as u16
+                }
+                Constraint::Fill => continue,
+            };
+
+            *size = wanted.min(remaining);
+            remaining -= *size;
+        }
+
+        let fills: Vec<usize> = self
+            .constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, constraint)| **constraint == Constraint::Fill)
+            .map(|(i, _)| i)
+            .collect();
+
+        if let Some(&last) = fills.last() {
+            let share = remaining / fills.len() as u16;
+            for &i in &fills {
+                sizes[i] = share;
+            }
+            sizes[last] += remaining - share * fills.len() as u16;
+        }
+
+        let mut offset = 0;
+        sizes
+            .into_iter()
+            .map(|size| {
+                let rect = match self.direction {
+                    Direction::Horizontal => Rect::new(area.x + offset, area.y, size, area.height),
+                    Direction::Vertical => Rect::new(area.x, area.y + offset, area.width, size),
+                };
+                offset += size;
+                rect
+            })
+            .collect()
+    }
+}
+
+/// The regions of the game screen. Every panel derives its position from here so they cannot
+/// disagree about where the others are.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScreenLayout {
+    /// The world viewport the camera shows.
+    pub map: Rect,
+    /// The line dividing the map from the panels below it.
+    pub separator: Rect,
+    pub log: Rect,
+    pub status: Rect,
+}
+
+impl ScreenLayout {
+    pub fn compute(width: u16, height: u16) -> Self {
+        let rows = Layout::vertical([
+            Constraint::Fill,
+            Constraint::Fixed(1),
+            Constraint::Percentage(30),
+        ])
+        .split(Rect::new(0, 0, width, height));
+
+        let panels = Layout::horizontal([
+            Constraint::Percentage(50),
+            Constraint::Fixed(1),
+            Constraint::Fill,
+        ])
+        .split(rows[2]);
+
+        ScreenLayout {
+            map: rows[0],
+            separator: rows[1],
+            log: panels[0],
+            status: panels[2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_mixed_constraints() {
+        let rects = Layout::vertical([
+            Constraint::Fixed(2),
+            Constraint::Fill,
+            Constraint::Percentage(25),
+        ])
+        .split(Rect::new(0, 0, 10, 20));
+
+        assert_eq!(rects[0], Rect::new(0, 0, 10, 2));
+        assert_eq!(rects[1], Rect::new(0, 2, 10, 13));
+        assert_eq!(rects[2], Rect::new(0, 15, 10, 5));
+    }
+
+    #[test]
+    fn test_fill_regions_share_the_remainder() {
+        let rects = Layout::horizontal([Constraint::Fill, Constraint::Fill, Constraint::Fill])
+            .split(Rect::new(5, 1, 10, 3));
+
+        assert_eq!(rects[0], Rect::new(5, 1, 3, 3));
+        assert_eq!(rects[1], Rect::new(8, 1, 3, 3));
+        assert_eq!(rects[2], Rect::new(11, 1, 4, 3));
+    }
+
+    #[test]
+    fn test_overallocated_constraints_are_clamped() {
+        let rects = Layout::vertical([Constraint::Fixed(8), Constraint::Fixed(8)])
+            .split(Rect::new(0, 0, 4, 10));
+
+        assert_eq!(rects[0].height, 8);
+        assert_eq!(rects[1].height, 2);
+    }
+
+    #[test]
+    fn test_screen_layout_regions_do_not_overlap() {
+        let layout = ScreenLayout::compute(100, 30);
+
+        assert_eq!(layout.map, Rect::new(0, 0, 100, 20));
+        assert_eq!(layout.separator, Rect::new(0, 20, 100, 1));
+        assert_eq!(layout.log, Rect::new(0, 21, 50, 9));
+        assert_eq!(layout.status, Rect::new(51, 21, 49, 9));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::fov;
+use crate::theme;
+use crate::tile::Coord;
+use crossterm::style::Color;
+use std::collections::HashMap;
+use std::ops::Add;
+
+/// The color shaded cells without a foreground of their own are assumed to have.
+const DEFAULT_FOREGROUND: Color = Color::Grey;
+
+/// Light reaching a position, per color channel, where 1.0 is full brightness.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Light {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl Light {
+    pub const BLACK: Light = Light::new(0.0, 0.0, 0.0);
+
+    /// Light that is everywhere, so visible cells without a light source are dark but readable.
+    pub const AMBIENT: Light = Light::new(0.12, 0.12, 0.16);
+
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Light { r, g, b }
+    }
+
+    pub fn scaled(self, factor: f32) -> Light {
+        Light::new(self.r * factor, self.g * factor, self.b * factor)
+    }
+}
+
+impl Add for Light {
+    type Output = Light;
+
+    fn add(self, other: Light) -> Light {
+        Light::new(self.r + other.r, self.g + other.g, self.b + other.b)
+    }
+}
+
+/// Something that casts light around a position.
+#[derive(Clone, Copy, Debug)]
+pub struct LightSource {
+    pub position: Coord,
+    /// How far the light reaches, in tiles. It fades out linearly towards this distance.
+    pub radius: i32,
+    pub color: Light,
+}
+
+/// LightMap holds the light accumulated from every source at each position they reach.
+pub struct LightMap {
+    light: HashMap<Coord, Light>,
+}
+
+impl LightMap {
+    /// Casts every source, letting light reach only the positions the source could see, so
+    /// opaque tiles throw shadows.
+    pub fn compute(sources: &[LightSource], is_opaque: impl Fn(Coord) -> bool) -> Self {
+        let mut light = HashMap::new();
+
+        for source in sources.iter().filter(|source| source.radius > 0) {
+            let (source_x, source_y) = source.position;
+
+            for position in fov::compute_fov(source.position, source.radius, &is_opaque) {
+                let dx = (position.0 - source_x) as f32;
+                let dy = (position.1 - source_y) as f32;
+                let distance = (dx * dx + dy * dy).sqrt();
+                let falloff = 1.0 - distance / (source.radius as f32 + 1.0);
+
+                let total = light.entry(position).or_insert(Light::BLACK);
+                *total = *total + source.color.scaled(falloff);
+            }
+        }
+
+        LightMap { light }
+    }
+
+    /// The light at a position including the ambient light.
+    pub fn light_at(&self, position: Coord) -> Light {
+        self.light.get(&position).copied().unwrap_or(Light::BLACK) + Light::AMBIENT
+    }
+}
+
+/// Multiplies a color by the light falling on it. Channels never get brighter than the color
+/// itself, however much light there is.
+pub fn shade(color: Option<Color>, light: Light) -> Color {
+    let color = color.unwrap_or(DEFAULT_FOREGROUND);
+    let Some((r, g, b)) = theme::to_rgb(color) else {
+        return color;
+    };
+
+    let channel = |value: u8, light: f32| (value as f32 * light.clamp(0.0, 1.0)).round() as u8;
+
+    Color::Rgb {
+        r: channel(r, light.r),
+        g: channel(g, light.g),
+        b: channel(b, light.b),
+    }
+}
+
+/// The torch the player carries. It burns fuel over time, and its light shrinks as the fuel
+/// runs out.
+#[derive(Clone, Copy, Debug)]
+pub struct Torch {
+    /// Seconds of burning left.
+    pub fuel: f32,
+    pub max_fuel: f32,
+    pub max_radius: i32,
+}
+
+impl Torch {
+    pub const COLOR: Light = Light::new(1.0, 0.85, 0.6);
+
+    pub fn new(max_fuel: f32, max_radius: i32) -> Self {
+        Torch {
+            fuel: max_fuel,
+            max_fuel,
+            max_radius,
+        }
+    }
+
+    pub fn burn(&mut self, seconds: f32) {
+        self.fuel = (self.fuel - seconds).max(0.0);
+    }
+
+    /// Adds fuel, up to what the torch holds.
+    pub fn refuel(&mut self, seconds: f32) {
+        self.fuel = (self.fuel + seconds).min(self.max_fuel);
+    }
+
+    /// How much fuel is left, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f32 {
+        if self.max_fuel > 0.0 {
+            self.fuel / self.max_fuel
+        } else {
+            0.0
+        }
+    }
+
+    /// The light radius for the remaining fuel. A torch with any fuel left lights at least the
+    /// tiles next to it; a burnt out one casts no light.
+    pub fn radius(&self) -> i32 {
+        if self.fuel <= 0.0 {
+            return 0;
+        }
+
+        ((self.max_radius as f32 * self.fraction()).ceil() as i32).max(1)
+    }
+
+    pub fn light(&self, position: Coord) -> LightSource {
+        LightSource {
+            position,
+            radius: self.radius(),
+            color: Torch::COLOR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_falls_off_with_distance() {
+        let source = LightSource {
+            position: (0, 0),
+            radius: 4,
+            color: Light::new(1.0, 1.0, 1.0),
+        };
+        let map = LightMap::compute(&[source], |_| false);
+
+        let near = map.light_at((1, 0));
+        let far = map.light_at((3, 0));
+
+        assert!(near.r > far.r);
+        assert_eq!(map.light_at((6, 0)), Light::AMBIENT);
+    }
+
+    #[test]
+    fn test_opaque_tiles_cast_shadows() {
+        let source = LightSource {
+            position: (0, 0),
+            radius: 6,
+            color: Light::new(1.0, 1.0, 1.0),
+        };
+        let map = LightMap::compute(&[source], |p| p == (1, 0));
+
+        assert!(map.light_at((1, 0)).r > Light::AMBIENT.r);
+        assert_eq!(map.light_at((3, 0)), Light::AMBIENT);
+    }
+
+    #[test]
+    fn test_shade_scales_each_channel() {
+        let white = Color::Rgb {
+            r: 200,
+            g: 200,
+            b: 200,
+        };
+
+        assert_eq!(
+            shade(Some(white), Light::new(0.5, 2.0, 0.0)),
+            Color::Rgb { r: 100, g: 200, b: 0 }
+        );
+    }
+
+    #[test]
+    fn test_torch_radius_shrinks_with_fuel() {
+        let mut torch = Torch::new(100.0, 8);
+        assert_eq!(torch.radius(), 8);
+
+        torch.burn(60.0);
+        assert_eq!(torch.radius(), 4);
+
+        torch.burn(39.0);
+        assert_eq!(torch.radius(), 1);
+
+        torch.burn(5.0);
+        assert_eq!(torch.fuel, 0.0);
+        assert_eq!(torch.radius(), 0);
+    }
+}
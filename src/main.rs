@@ -1,108 +1,139 @@
+mod app;
+mod backend;
 mod bounding_box;
 mod camera;
 mod drawable;
 mod enemy;
+mod fov;
 mod frame;
 mod game;
+mod glyphs;
 mod health;
+mod keymap;
+mod layout;
+mod lighting;
+mod mapgen;
+mod options;
 mod pathfinding;
 mod player;
+mod renderer;
+mod save;
+mod scheduler;
+mod theme;
 mod tile;
+mod timestep;
+mod widget;
 mod activity_log;
 
+use crate::app::App;
+use crate::backend::headless::HeadlessBackend;
+use crate::backend::terminal::TerminalBackend;
+use crate::backend::Backend;
 use crate::frame::Frame;
-use crate::game::Game;
-use crossterm::cursor::{Hide, Show};
-use crossterm::style::{ResetColor, SetBackgroundColor, SetForegroundColor};
-use crossterm::terminal::{
-    BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate, SetTitle,
-};
-use crossterm::{
-    cursor::MoveTo,
-    event::{poll, read, Event, KeyCode},
-    execute,
-    style::Print,
-    terminal::{
-        disable_raw_mode, enable_raw_mode, size, EnterAlternateScreen, LeaveAlternateScreen,
-    },
-};
-use std::io::{stdout, Result, Write};
-use std::time::Duration;
+use crate::options::Options;
+use crate::theme::ColorDepth;
+use crate::timestep::FixedTimestep;
+use crossterm::event::Event;
+use std::io::{IsTerminal, Result};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-fn main() -> Result<()> {
-    let mut stdout = stdout();
-
-    enable_raw_mode()?;
-    execute!(
-        stdout,
-        EnterAlternateScreen,
-        Hide,
-        Clear(ClearType::Purge),
-        SetTitle("Cave Diver Terminal")
-    )?;
-
-    let (width, height) = size()?;
-
-    let mut game = Game::new(width, height);
-
-    loop {
-        execute!(&mut stdout, BeginSynchronizedUpdate)?;
-        let (width, height) = size()?;
+/// Number of frames simulated by `--headless` before the final screen is printed.
+const HEADLESS_FRAMES: usize = 60;
 
-        game.update(width, height);
+/// Simulation ticks per second. Everything that happens over time advances in these steps.
+const TICK_RATE: u32 = 30;
 
-        let mut frame = Frame::new(game.camera.x, game.camera.y, width, height);
+/// Most frames presented per second.
+const MAX_FPS: u32 = 60;
 
-        game.draw(&mut frame);
-        game.draw_ui(&mut frame);
-
-        render_frame(&mut stdout, &frame)?;
-
-        stdout.flush()?;
-
-        execute!(&mut stdout, EndSynchronizedUpdate)?;
+fn main() -> Result<()> {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
 
-        if game.request_exit {
-            break;
+    if options.headless {
+        let mut backend = HeadlessBackend::new(80, 24);
+        run(&mut backend, &options, Some(HEADLESS_FRAMES))?;
+        // Colors only make sense on a terminal; piped into a file or CI log the screen stays
+        // plain text
+        if options.color_depth != ColorDepth::Monochrome && std::io::stdout().is_terminal() {
+            println!("{}", backend.to_ansi());
+        } else {
+            println!("{}", backend.to_text());
         }
+
+        return Ok(());
     }
 
+    let mut backend = TerminalBackend::new(options.color_depth)?;
+    let result = run(&mut backend, &options, None);
+
     // Clean up
-    execute!(stdout, LeaveAlternateScreen, Show)?;
-    disable_raw_mode()?;
-    Ok(())
+    backend.restore()?;
+    result
 }
 
-fn render_frame(stdout: &mut std::io::Stdout, frame: &Frame) -> Result<()> {
-    use crossterm::{
-        cursor::MoveTo,
-        style::Print,
-        terminal::{Clear, ClearType},
-    };
+/// Runs the game loop on `backend` until the player quits or `max_frames` have been presented.
+fn run<B: Backend>(backend: &mut B, options: &Options, max_frames: Option<usize>) -> Result<()> {
+    let (width, height) = backend.size()?;
 
-    execute!(stdout, Clear(ClearType::Purge))?;
+    let mut app = App::new(width, height, options.clone());
+    let mut frame = Frame::new(0, 0, width, height);
+    frame.set_glyphs(options.glyphs);
+    frame.set_theme(Rc::new(options.theme.clone()));
 
-    let mut x = 0;
-    let mut y = 0;
+    let mut frames = 0;
+    let mut timestep = FixedTimestep::new(TICK_RATE, Instant::now());
+    let frame_time = Duration::from_secs(1) / MAX_FPS;
+    let mut last_render: Option<Instant> = None;
+    let mut events = Vec::new();
 
-    for (i, cell) in frame.buffer.iter().enumerate() {
-        x = (i as u16) % frame.width;
-        y = (i as u16) / frame.width;
+    loop {
+        // Sleep in the input poll until the next tick or frame is due, then drain whatever
+        // else is already queued
+        let now = Instant::now();
+        let until_render = last_render
+            .map_or(Duration::ZERO, |last| (last + frame_time).saturating_duration_since(now));
+        let mut next = backend.poll_event(timestep.until_next_tick(now).min(until_render))?;
+
+        while let Some(event) = next {
+            match event {
+                Event::Resize(width, height) => {
+                    app.resize(width, height);
+                    frame.resize(width, height);
+                }
+                event => events.push(event),
+            }
+            next = backend.poll_event(Duration::ZERO)?;
+        }
 
-        execute!(stdout, MoveTo(x, y))?;
+        for _ in 0..timestep.advance(Instant::now()) {
+            app.update(&events, timestep.tick);
+            events.clear();
+        }
 
-        if let Some(fg_color) = cell.fg {
-            execute!(stdout, SetForegroundColor(fg_color))?;
+        if app.request_exit {
+            break;
         }
 
-        if let Some(bg_color) = cell.bg {
-            execute!(stdout, SetBackgroundColor(bg_color))?;
+        let now = Instant::now();
+        if last_render.is_some_and(|last| now - last < frame_time) {
+            continue;
         }
+        last_render = Some(now);
 
-        execute!(stdout, Print(cell.ch))?;
+        app.draw(&mut frame);
 
-        if cell.fg.is_some() || cell.bg.is_some() {
-            execute!(stdout, ResetColor)?;
+        backend.present(&frame)?;
+        frames += 1;
+
+        if max_frames.is_some_and(|max| frames >= max) {
+            break;
         }
     }
 
@@ -0,0 +1,339 @@
+pub(crate) mod cave;
+pub(crate) mod dungeon;
+pub(crate) mod map_file;
+pub(crate) mod room;
+
+use crate::drawable::fixture::{Fixture, FixtureKind};
+use crate::drawable::item::{Item, ItemKind};
+use crate::drawable::Drawable;
+use crate::enemy::goblin::{Goblin, GoblinKind};
+use crate::mapgen::cave::CaveGenerator;
+use crate::mapgen::dungeon::DungeonGenerator;
+use crate::mapgen::map_file::{MapError, MapFile};
+use crate::mapgen::room::{Room, Side};
+use crate::tile::{Coord, TileKind, TileMap};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::rc::Rc;
+
+/// The bottom level. It has no stairs further down.
+pub const MAX_DEPTH: u32 = 50;
+
+/// Size of generated caves, in tiles.
+const CAVE_WIDTH: i32 = 80;
+const CAVE_HEIGHT: i32 = 50;
+
+const CAVE_GOBLINS: usize = 4;
+/// Closest a goblin is placed to the player's start, in steps ignoring walls.
+const GOBLIN_MIN_DISTANCE: i32 = 12;
+const CAVE_FUNGI: usize = 10;
+
+/// Seconds of torch light in a flask of oil on the first depth.
+const OIL_SECONDS: u32 = 30;
+
+/// Size of generated dungeons, in tiles.
+const DUNGEON_WIDTH: i32 = 90;
+const DUNGEON_HEIGHT: i32 = 60;
+
+/// A level ready to be played: its terrain, everything in it, and where the player starts and
+/// leaves.
+pub struct Level {
+    pub tiles: TileMap,
+    pub drawables: Vec<Box<dyn Drawable>>,
+    pub start: Coord,
+    pub exit: Coord,
+}
+
+/// The kinds of level a game can be played on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LevelKind {
+    /// The hand-built level the game started out with.
+    Demo,
+    Cave,
+    /// Rooms joined by corridors.
+    Dungeon,
+    /// A level read from a map file, which is the same whatever the seed.
+    Map(Rc<MapFile>),
+}
+
+impl LevelKind {
+    /// The name of a generated kind. Map files go by their path instead, see `Display`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LevelKind::Demo => "demo",
+            LevelKind::Cave => "cave",
+            LevelKind::Dungeon => "dungeon",
+            LevelKind::Map(_) => "map",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "demo" => Some(LevelKind::Demo),
+            "cave" => Some(LevelKind::Cave),
+            "dungeon" => Some(LevelKind::Dungeon),
+            _ => None,
+        }
+    }
+
+    /// A generated kind by name, or otherwise the map file at that path.
+    pub fn from_spec(spec: &str) -> Result<Self, MapError> {
+        match LevelKind::from_name(spec) {
+            Some(kind) => Ok(kind),
+            None => Ok(LevelKind::Map(Rc::new(MapFile::load(spec)?))),
+        }
+    }
+
+    /// Builds the level at `depth`. Hand-made levels are the same at any depth.
+    pub fn generate(&self, depth: u32, rng: &mut impl Rng) -> Level {
+        let difficulty = Difficulty::new(depth);
+
+        match self {
+            LevelKind::Demo => Level::demo(),
+            LevelKind::Cave => cave_level(difficulty, rng),
+            LevelKind::Dungeon => dungeon_level(difficulty, rng),
+            LevelKind::Map(map) => map.level(),
+        }
+    }
+
+    /// The kind of the levels below this one. There is only one of a hand-made level, so the
+    /// way down from it leads into generated dungeons.
+    pub fn below(&self) -> LevelKind {
+        match self {
+            LevelKind::Demo | LevelKind::Map(_) => LevelKind::Dungeon,
+            kind => kind.clone(),
+        }
+    }
+}
+
+/// How many enemies a generated level gets, of which kinds, and how much its loot is worth.
+/// All of it grows with depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Difficulty {
+    pub depth: u32,
+}
+
+impl Difficulty {
+    pub fn new(depth: u32) -> Self {
+        Difficulty {
+            depth: depth.max(1),
+        }
+    }
+
+    /// Goblins in a level that has `base` of them on the first depth, with half as many again
+    /// for every level further down.
+    pub fn goblins(self, base: usize) -> usize {
+        base + base * (self.depth as usize - 1) / 2
+    }
+
+    /// Scouts and warriors live near the surface, and brutes take over further down.
+    pub fn goblin_kind(self, rng: &mut impl Rng) -> GoblinKind {
+        let roll = rng.gen_range(0..10) + 2 * (self.depth - 1);
+
+        match roll {
+            0..4 => GoblinKind::Scout,
+            4..12 => GoblinKind::Warrior,
+            _ => GoblinKind::Brute,
+        }
+    }
+
+    pub fn items(self) -> usize {
+        2 + self.depth as usize / 2
+    }
+
+    /// A potion or a flask of oil, with more in it the deeper it is found.
+    pub fn item(self, (x, y): Coord, rng: &mut impl Rng) -> Item {
+        if rng.gen_bool(0.5) {
+            Item::new(x, y, ItemKind::Potions, 1 + self.depth / 3)
+        } else {
+            Item::new(x, y, ItemKind::Oil, OIL_SECONDS * (1 + self.depth / 2))
+        }
+    }
+}
+
+impl fmt::Display for LevelKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelKind::Map(map) => write!(f, "{}", map.path.display()),
+            kind => write!(f, "{}", kind.name()),
+        }
+    }
+}
+
+impl Level {
+    /// One large room with a row of trees, a pool of lava, a pond, a goblin, and a few lights.
+    pub fn demo() -> Self {
+        let mut tiles = TileMap::new();
+        Room::new(2, 2, 50, 55).carve(&mut tiles);
+
+        for x in 15..=18 {
+            tiles.set((x, 15), TileKind::Tree);
+        }
+        for y in 16..=18 {
+            tiles.set((18, y), TileKind::Tree);
+        }
+        for y in 26..28 {
+            for x in 30..34 {
+                tiles.set((x, y), TileKind::Lava);
+            }
+        }
+        for y in 38..43 {
+            for x in 10..17 {
+                let edge = y == 38 || y == 42 || x == 10 || x == 16;
+                let kind = if edge {
+                    TileKind::Water
+                } else {
+                    TileKind::DeepWater
+                };
+                tiles.set((x, y), kind);
+            }
+        }
+        for position in [(40, 12), (41, 12), (41, 13), (42, 13)] {
+            tiles.set(position, TileKind::Rubble);
+        }
+
+        let drawables: Vec<Box<dyn Drawable>> = vec![
+            Box::new(Goblin::new(20, 10)),
+            Box::new(Fixture::new(8, 2, FixtureKind::Sconce)),
+            Box::new(Fixture::new(30, 2, FixtureKind::Sconce)),
+            Box::new(Fixture::new(2, 20, FixtureKind::Sconce)),
+            Box::new(Fixture::new(24, 20, FixtureKind::Fungus)),
+            Box::new(Fixture::new(25, 21, FixtureKind::Fungus)),
+            Box::new(Fixture::new(12, 30, FixtureKind::Fungus)),
+        ];
+
+        Level {
+            tiles,
+            drawables,
+            start: (10, 10),
+            exit: (45, 50),
+        }
+    }
+}
+
+/// A cellular automaton cave with goblins away from the start, glowing fungus along the walls
+/// and loot lying around.
+pub fn cave_level(difficulty: Difficulty, rng: &mut impl Rng) -> Level {
+    let cave = CaveGenerator::new(CAVE_WIDTH, CAVE_HEIGHT).generate(rng);
+    let mut tiles = TileMap::new();
+    for position in cave.visible_walls() {
+        tiles.set(position, TileKind::Wall);
+    }
+    let mut drawables: Vec<Box<dyn Drawable>> = Vec::new();
+
+    let mut floors: Vec<Coord> = cave.floors().collect();
+    floors.shuffle(rng);
+
+    let (start_x, start_y) = cave.start;
+    let far_away = floors
+        .iter()
+        .filter(|&&(x, y)| (x - start_x).abs() + (y - start_y).abs() >= GOBLIN_MIN_DISTANCE)
+        .filter(|&&position| position != cave.exit);
+    for &(x, y) in far_away.take(difficulty.goblins(CAVE_GOBLINS)) {
+        let kind = difficulty.goblin_kind(rng);
+        drawables.push(Box::new(Goblin::of_kind(x, y, kind)));
+    }
+
+    let along_walls = floors
+        .iter()
+        .filter(|&&(x, y)| cave.is_wall((x - 1, y)) || cave.is_wall((x + 1, y)))
+        .filter(|&&position| position != cave.start && position != cave.exit);
+    for &(x, y) in along_walls.take(CAVE_FUNGI) {
+        drawables.push(Box::new(Fixture::new(x, y, FixtureKind::Fungus)));
+    }
+
+    let loot_spots = floors
+        .iter()
+        .rev()
+        .filter(|&&position| position != cave.start && position != cave.exit);
+    for &position in loot_spots.take(difficulty.items()) {
+        drawables.push(Box::new(difficulty.item(position, rng)));
+    }
+
+    Level {
+        tiles,
+        drawables,
+        start: cave.start,
+        exit: cave.exit,
+    }
+}
+
+/// Rooms joined by corridors. The player starts in a random room and leaves from the room the
+/// most corridors away, every other room holds goblins, loot is spread over the rooms, and each
+/// room is lit by a sconce.
+pub fn dungeon_level(difficulty: Difficulty, rng: &mut impl Rng) -> Level {
+    let dungeon = DungeonGenerator::new(DUNGEON_WIDTH, DUNGEON_HEIGHT).generate(rng);
+    let tiles = dungeon.tiles();
+    let mut drawables: Vec<Box<dyn Drawable>> = Vec::new();
+
+    let start_room = rng.gen_range(0..dungeon.rooms.len());
+    let distances = dungeon.graph.distances(start_room, dungeon.rooms.len());
+    let exit_room = (0..dungeon.rooms.len())
+        .max_by_key(|&room| distances[room])
+        .unwrap_or(start_room);
+
+    for (index, room) in dungeon.rooms.iter().enumerate() {
+        if index != start_room && index != exit_room {
+            for _ in 0..difficulty.goblins(1) {
+                let (x, y) = inside(room, rng);
+                let kind = difficulty.goblin_kind(rng);
+                drawables.push(Box::new(Goblin::of_kind(x, y, kind)));
+            }
+        }
+
+        // Beside the top door, whether or not the room has one.
+        let (door_x, door_y) = room.door(Side::Top);
+        drawables.push(Box::new(Fixture::new(door_x - 1, door_y, FixtureKind::Sconce)));
+    }
+
+    let start = dungeon.rooms[start_room].center();
+    let exit = dungeon.rooms[exit_room].center();
+    for _ in 0..difficulty.items() {
+        let room = &dungeon.rooms[rng.gen_range(0..dungeon.rooms.len())];
+        let position = inside(room, rng);
+        if position != start && position != exit {
+            drawables.push(Box::new(difficulty.item(position, rng)));
+        }
+    }
+
+    Level {
+        tiles,
+        drawables,
+        start,
+        exit,
+    }
+}
+
+/// A random tile inside a room, away from its walls.
+fn inside(room: &Room, rng: &mut impl Rng) -> Coord {
+    (
+        rng.gen_range(room.x + 1..room.x + room.width as i32 - 1),
+        rng.gen_range(room.y + 1..room.y + room.height as i32 - 1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_deeper_levels_are_harder() {
+        let shallow = Difficulty::new(1);
+        let deep = Difficulty::new(5);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        assert!(deep.goblins(4) > shallow.goblins(4));
+        assert!(deep.items() > shallow.items());
+        assert!((0..100).all(|_| shallow.goblin_kind(&mut rng) != GoblinKind::Brute));
+        assert!((0..100).any(|_| deep.goblin_kind(&mut rng) == GoblinKind::Brute));
+    }
+
+    #[test]
+    fn test_hand_made_levels_lead_down_into_dungeons() {
+        assert_eq!(LevelKind::Demo.below(), LevelKind::Dungeon);
+        assert_eq!(LevelKind::Cave.below(), LevelKind::Cave);
+    }
+}
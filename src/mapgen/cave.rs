@@ -0,0 +1,283 @@
+use crate::tile::Coord;
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// Caves that come out smaller than `min_open` are generated again up to this many times
+/// before the generator gives up and returns a single open chamber.
+const MAX_ATTEMPTS: u32 = 20;
+
+/// Settings for the cellular automaton that carves caves. The map starts as random noise and
+/// every smoothing pass turns each cell into a wall or floor depending on how many of its eight
+/// neighbours are walls, which grows the noise into rounded caverns.
+#[derive(Clone, Debug)]
+pub struct CaveGenerator {
+    pub width: i32,
+    pub height: i32,
+    /// Chance of each cell starting out as a wall.
+    pub fill: f64,
+    pub smoothing_passes: u32,
+    /// A floor cell becomes a wall when at least this many of its neighbours are walls.
+    pub birth_limit: usize,
+    /// A wall stays a wall when at least this many of its neighbours are walls.
+    pub survival_limit: usize,
+    /// Smallest share of the map the cave has to cover.
+    pub min_open: f64,
+}
+
+/// A generated cave. Everything outside the map counts as wall, and every floor cell can be
+/// reached from every other one.
+#[derive(Clone, Debug)]
+pub struct Cave {
+    pub width: i32,
+    pub height: i32,
+    walls: Vec<bool>,
+    pub start: Coord,
+    /// The floor cell furthest from `start`.
+    pub exit: Coord,
+}
+
+impl CaveGenerator {
+    pub fn new(width: i32, height: i32) -> Self {
+        CaveGenerator {
+            width,
+            height,
+            fill: 0.45,
+            smoothing_passes: 5,
+            birth_limit: 5,
+            survival_limit: 4,
+            min_open: 0.35,
+        }
+    }
+
+    pub fn generate(&self, rng: &mut impl Rng) -> Cave {
+        for _ in 0..MAX_ATTEMPTS {
+            let mut cave = self.noise(rng);
+
+            for _ in 0..self.smoothing_passes {
+                cave = self.smooth(&cave);
+            }
+
+            let open = cave.keep_largest_region();
+            if open as f64 >= self.min_open * (self.width * self.height) as f64 {
+                cave.place_start_and_exit(rng);
+                return cave;
+            }
+        }
+
+        let mut cave = self.empty(|x, y| cave_border(self.width, self.height, x, y));
+        cave.place_start_and_exit(rng);
+        cave
+    }
+
+    fn empty(&self, is_wall: impl Fn(i32, i32) -> bool) -> Cave {
+        let mut walls = Vec::with_capacity((self.width * self.height).max(0) as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                walls.push(is_wall(x, y));
+            }
+        }
+
+        Cave {
+            width: self.width,
+            height: self.height,
+            walls,
+            start: (0, 0),
+            exit: (0, 0),
+        }
+    }
+
+    /// Random walls with a solid border, so the cave is always closed.
+    fn noise(&self, rng: &mut impl Rng) -> Cave {
+        let mut walls = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                walls.push(cave_border(self.width, self.height, x, y) || rng.gen_bool(self.fill));
+            }
+        }
+
+        let mut cave = self.empty(|_, _| true);
+        cave.walls = walls;
+        cave
+    }
+
+    fn smooth(&self, cave: &Cave) -> Cave {
+        self.empty(|x, y| {
+            if cave_border(self.width, self.height, x, y) {
+                return true;
+            }
+
+            let walls = cave.wall_neighbours((x, y));
+            if cave.is_wall((x, y)) {
+                walls >= self.survival_limit
+            } else {
+                walls >= self.birth_limit
+            }
+        })
+    }
+}
+
+fn cave_border(width: i32, height: i32, x: i32, y: i32) -> bool {
+    x == 0 || y == 0 || x == width - 1 || y == height - 1
+}
+
+impl Cave {
+    fn index(&self, (x, y): Coord) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some((y * self.width + x) as usize)
+    }
+
+    pub fn is_wall(&self, position: Coord) -> bool {
+        self.index(position).is_none_or(|index| self.walls[index])
+    }
+
+    fn wall_neighbours(&self, (x, y): Coord) -> usize {
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0) && self.is_wall((x + dx, y + dy)) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    pub fn floors(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&position| !self.is_wall(position))
+    }
+
+    /// Walls next to a floor cell, including diagonally. Solid rock further in can never be
+    /// seen or reached, so only these need to exist in the level.
+    pub fn visible_walls(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&position| self.is_wall(position) && self.wall_neighbours(position) < 8)
+    }
+
+    /// Distances in steps from `from` to every floor cell it connects to, moving the way
+    /// goblins do without diagonals.
+    fn distances(&self, from: Coord) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.walls.len()];
+        let mut queue = VecDeque::new();
+
+        if let Some(index) = self.index(from).filter(|&index| !self.walls[index]) {
+            distances[index] = Some(0);
+            queue.push_back((from, 0));
+        }
+
+        while let Some(((x, y), distance)) = queue.pop_front() {
+            for next in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                let Some(index) = self.index(next) else {
+                    continue;
+                };
+
+                if !self.walls[index] && distances[index].is_none() {
+                    distances[index] = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Fills every pocket of floor but the largest one in, so the whole cave is connected.
+    /// Returns the size of what is left.
+    fn keep_largest_region(&mut self) -> usize {
+        let mut region = vec![None; self.walls.len()];
+        let mut sizes = Vec::new();
+
+        for position in self.floors().collect::<Vec<_>>() {
+            let index = self.index(position).unwrap_or_default();
+            if region[index].is_some() {
+                continue;
+            }
+
+            let mut size = 0;
+            for (index, distance) in self.distances(position).into_iter().enumerate() {
+                if distance.is_some() {
+                    region[index] = Some(sizes.len());
+                    size += 1;
+                }
+            }
+            sizes.push(size);
+        }
+
+        let Some((largest, &size)) = sizes.iter().enumerate().max_by_key(|(_, size)| **size) else {
+            return 0;
+        };
+
+        for (wall, region) in self.walls.iter_mut().zip(region) {
+            if region != Some(largest) {
+                *wall = true;
+            }
+        }
+
+        size
+    }
+
+    /// Starts the player on a random floor cell and puts the exit as far away as possible.
+    fn place_start_and_exit(&mut self, rng: &mut impl Rng) {
+        let floors: Vec<Coord> = self.floors().collect();
+        let Some(&start) = floors.get(rng.gen_range(0..floors.len().max(1))) else {
+            return;
+        };
+
+        let distances = self.distances(start);
+        let exit = floors
+            .iter()
+            .copied()
+            .max_by_key(|&position| self.index(position).and_then(|index| distances[index]))
+            .unwrap_or(start);
+
+        self.start = start;
+        self.exit = exit;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_every_floor_is_connected_to_the_start() {
+        for seed in 0..10 {
+            let cave = CaveGenerator::new(60, 40).generate(&mut StdRng::seed_from_u64(seed));
+            let distances = cave.distances(cave.start);
+
+            assert!(!cave.is_wall(cave.start));
+            assert!(!cave.is_wall(cave.exit));
+            assert_ne!(cave.start, cave.exit);
+            for floor in cave.floors() {
+                assert!(distances[cave.index(floor).unwrap()].is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cave_is_closed_and_open_enough() {
+        let generator = CaveGenerator::new(50, 30);
+        let cave = generator.generate(&mut StdRng::seed_from_u64(7));
+
+        assert!((0..50).all(|x| cave.is_wall((x, 0)) && cave.is_wall((x, 29))));
+        assert!(cave.floors().count() as f64 >= generator.min_open * (50.0 * 30.0));
+    }
+
+    #[test]
+    fn test_hopeless_settings_fall_back_to_a_chamber() {
+        let mut generator = CaveGenerator::new(12, 8);
+        generator.fill = 1.0;
+
+        let cave = generator.generate(&mut StdRng::seed_from_u64(1));
+
+        assert_eq!(cave.floors().count(), 10 * 6);
+    }
+}
@@ -0,0 +1,395 @@
+use crate::mapgen::room::{Room, Side};
+use crate::pathfinding::{find_path, Point};
+use crate::tile::{Coord, TileKind, TileMap};
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+
+/// Settings for a dungeon of rooms joined by corridors. The map is cut in two again and again
+/// (binary space partitioning) until the pieces are small, then every piece gets a room and the
+/// two halves of every cut are joined by a corridor between their closest rooms.
+#[derive(Clone, Debug)]
+pub struct DungeonGenerator {
+    pub width: i32,
+    pub height: i32,
+    /// Pieces smaller than twice this along both sides are not cut any further.
+    pub min_leaf: i32,
+    /// Smallest room, counting its walls.
+    pub min_room: i32,
+    /// Largest room, counting its walls.
+    pub max_room: i32,
+}
+
+/// A generated dungeon. Rooms are closed apart from the doors corridors lead to, and every
+/// room can be reached from every other one.
+#[derive(Clone, Debug)]
+pub struct Dungeon {
+    pub width: i32,
+    pub height: i32,
+    pub rooms: Vec<Room>,
+    /// Floor tiles outside the rooms.
+    pub corridors: HashSet<Coord>,
+    pub graph: RoomGraph,
+}
+
+/// Which rooms have a corridor between them, by index into `Dungeon::rooms`.
+#[derive(Clone, Debug, Default)]
+pub struct RoomGraph {
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// A piece of the map, which ends up holding exactly one room.
+#[derive(Clone, Copy, Debug)]
+struct Leaf {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl DungeonGenerator {
+    pub fn new(width: i32, height: i32) -> Self {
+        DungeonGenerator {
+            width,
+            height,
+            min_leaf: 10,
+            min_room: 5,
+            max_room: 14,
+        }
+    }
+
+    pub fn generate(&self, rng: &mut impl Rng) -> Dungeon {
+        let mut dungeon = Dungeon {
+            width: self.width,
+            height: self.height,
+            rooms: Vec::new(),
+            corridors: HashSet::new(),
+            graph: RoomGraph::default(),
+        };
+
+        // The outermost ring is left for the walls of corridors that run along the edge.
+        let root = Leaf {
+            x: 1,
+            y: 1,
+            width: self.width - 2,
+            height: self.height - 2,
+        };
+        self.split(root, &mut dungeon, rng);
+        debug_assert!(
+            dungeon
+                .graph
+                .distances(0, dungeon.rooms.len())
+                .iter()
+                .all(Option::is_some),
+            "some rooms could not be joined to the rest"
+        );
+
+        dungeon
+    }
+
+    /// Cuts a leaf in two, or places a room in it when it is too small to cut. Returns the
+    /// indices of the rooms inside it.
+    fn split(&self, leaf: Leaf, dungeon: &mut Dungeon, rng: &mut impl Rng) -> Vec<usize> {
+        let can_cut_across = leaf.width >= self.min_leaf * 2;
+        let can_cut_down = leaf.height >= self.min_leaf * 2;
+
+        let halves = match (can_cut_across, can_cut_down) {
+            (false, false) => None,
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            (true, true) if leaf.width > leaf.height => Some(true),
+            (true, true) if leaf.height > leaf.width => Some(false),
+            (true, true) => Some(rng.gen_bool(0.5)),
+        }
+        .map(|across| {
+            if across {
+                let cut = rng.gen_range(self.min_leaf..=leaf.width - self.min_leaf);
+                (
+                    Leaf { width: cut, ..leaf },
+                    Leaf {
+                        x: leaf.x + cut,
+                        width: leaf.width - cut,
+                        ..leaf
+                    },
+                )
+            } else {
+                let cut = rng.gen_range(self.min_leaf..=leaf.height - self.min_leaf);
+                (
+                    Leaf {
+                        height: cut,
+                        ..leaf
+                    },
+                    Leaf {
+                        y: leaf.y + cut,
+                        height: leaf.height - cut,
+                        ..leaf
+                    },
+                )
+            }
+        });
+
+        let Some((first, second)) = halves else {
+            dungeon.rooms.push(self.room_in(leaf, rng));
+            return vec![dungeon.rooms.len() - 1];
+        };
+
+        let mut rooms = self.split(first, dungeon, rng);
+        let others = self.split(second, dungeon, rng);
+        dungeon.connect_closest(&rooms, &others);
+        rooms.extend(others);
+        rooms
+    }
+
+    /// A room of random size somewhere in a leaf, keeping a tile of space all around so rooms
+    /// in neighbouring leaves never touch.
+    fn room_in(&self, leaf: Leaf, rng: &mut impl Rng) -> Room {
+        let max_width = (leaf.width - 2).min(self.max_room);
+        let max_height = (leaf.height - 2).min(self.max_room);
+        let width = rng.gen_range(self.min_room.min(max_width)..=max_width);
+        let height = rng.gen_range(self.min_room.min(max_height)..=max_height);
+        let x = rng.gen_range(leaf.x + 1..=leaf.x + leaf.width - 1 - width);
+        let y = rng.gen_range(leaf.y + 1..=leaf.y + leaf.height - 1 - height);
+
+        Room::closed(x, y, width as u16, height as u16)
+    }
+}
+
+impl Dungeon {
+    /// The room a position is in, counting its walls.
+    pub fn room_at(&self, position: Coord) -> Option<usize> {
+        self.rooms.iter().position(|room| room.contains(position))
+    }
+
+    /// Corridor tiles can be dug anywhere inside the map that is not part of a room.
+    fn can_dig(&self, (x, y): Coord) -> bool {
+        x > 0
+            && y > 0
+            && x < self.width - 1
+            && y < self.height - 1
+            && self.room_at((x, y)).is_none()
+    }
+
+    /// Walls around the corridors, including diagonally, so they are closed like the rooms.
+    pub fn corridor_walls(&self) -> HashSet<Coord> {
+        let mut walls = HashSet::new();
+
+        for &(x, y) in &self.corridors {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let position = (x + dx, y + dy);
+                    if !self.corridors.contains(&position) && self.room_at(position).is_none() {
+                        walls.insert(position);
+                    }
+                }
+            }
+        }
+
+        walls
+    }
+
+    /// The walls and doors of every room and the walls along the corridors. Everything else
+    /// inside them is floor.
+    pub fn tiles(&self) -> TileMap {
+        let mut tiles = TileMap::new();
+        for room in &self.rooms {
+            room.carve(&mut tiles);
+        }
+        for position in self.corridor_walls() {
+            tiles.set(position, TileKind::Wall);
+        }
+
+        tiles
+    }
+
+    /// Joins the closest pair of rooms between two groups that a corridor can be dug between.
+    fn connect_closest(&mut self, first: &[usize], second: &[usize]) {
+        let distance = |a: usize, b: usize| {
+            let (ax, ay) = self.rooms[a].center();
+            let (bx, by) = self.rooms[b].center();
+            (ax - bx).abs() + (ay - by).abs()
+        };
+
+        let mut pairs: Vec<_> = first
+            .iter()
+            .flat_map(|&a| second.iter().map(move |&b| (a, b)))
+            .collect();
+        pairs.sort_by_key(|&(a, b)| distance(a, b));
+
+        for (a, b) in pairs {
+            if self.connect(a, b) {
+                return;
+            }
+        }
+    }
+
+    /// Opens a door in each room on the side facing the other and digs a corridor between the
+    /// tiles just outside them. Returns whether there was room for the corridor.
+    fn connect(&mut self, a: usize, b: usize) -> bool {
+        let a_side = self.rooms[a].facing(self.rooms[b].center());
+        let b_side = self.rooms[b].facing(self.rooms[a].center());
+        let from = outside(&self.rooms[a], a_side);
+        let to = outside(&self.rooms[b], b_side);
+
+        let Some(path) = self.corridor_path(from, to) else {
+            return false;
+        };
+
+        self.rooms[a].door_sides[a_side as usize] = true;
+        self.rooms[b].door_sides[b_side as usize] = true;
+        self.corridors.extend(path);
+        self.graph.edges.push((a, b));
+        true
+    }
+
+    /// An L-shaped corridor when one fits around the rooms, otherwise the shortest one that
+    /// winds between them.
+    fn corridor_path(&self, (from_x, from_y): Coord, (to_x, to_y): Coord) -> Option<Vec<Coord>> {
+        let across = |y: i32| range(from_x, to_x).map(move |x| (x, y));
+        let down = |x: i32| range(from_y, to_y).map(move |y| (x, y));
+
+        let elbows = [
+            across(from_y).chain(down(to_x)).collect::<Vec<_>>(),
+            down(from_x).chain(across(to_y)).collect::<Vec<_>>(),
+        ];
+        if let Some(path) = elbows
+            .into_iter()
+            .find(|path| path.iter().all(|&position| self.can_dig(position)))
+        {
+            return Some(path);
+        }
+
+        let path = find_path(
+            Point {
+                x: from_x,
+                y: from_y,
+            },
+            Point { x: to_x, y: to_y },
+            |point| self.can_dig((point.x, point.y)),
+        )?;
+
+        Some(
+            std::iter::once((from_x, from_y))
+                .chain(path.into_iter().map(|point| (point.x, point.y)))
+                .collect(),
+        )
+    }
+}
+
+/// The tile just outside the door on one side of a room.
+fn outside(room: &Room, side: Side) -> Coord {
+    let (x, y) = room.door(side);
+    let (dx, dy) = side.outward();
+    (x + dx, y + dy)
+}
+
+/// Every number from `from` to `to`, both included, counting down when `to` is smaller.
+fn range(from: i32, to: i32) -> Box<dyn Iterator<Item = i32>> {
+    if from <= to {
+        Box::new(from..=to)
+    } else {
+        Box::new((to..=from).rev())
+    }
+}
+
+impl RoomGraph {
+    pub fn neighbours(&self, room: usize) -> impl Iterator<Item = usize> + '_ {
+        self.edges.iter().filter_map(move |&(a, b)| match room {
+            _ if a == room => Some(b),
+            _ if b == room => Some(a),
+            _ => None,
+        })
+    }
+
+    /// How many corridors away every room is from `from`, or `None` for rooms it does not
+    /// connect to.
+    pub fn distances(&self, from: usize, rooms: usize) -> Vec<Option<u32>> {
+        let mut distances = vec![None; rooms];
+        let mut queue = VecDeque::new();
+
+        if from < rooms {
+            distances[from] = Some(0);
+            queue.push_back((from, 0));
+        }
+
+        while let Some((room, distance)) = queue.pop_front() {
+            for next in self.neighbours(room) {
+                if distances[next].is_none() {
+                    distances[next] = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_room_graph_connects_every_room() {
+        for seed in 0..10 {
+            let dungeon = DungeonGenerator::new(80, 50).generate(&mut StdRng::seed_from_u64(seed));
+            let distances = dungeon.graph.distances(0, dungeon.rooms.len());
+
+            assert!(dungeon.rooms.len() >= 4);
+            assert!(distances.iter().all(Option::is_some));
+        }
+    }
+
+    #[test]
+    fn test_every_room_can_be_walked_to_and_nothing_leaks() {
+        for seed in 0..10 {
+            let dungeon = DungeonGenerator::new(80, 50).generate(&mut StdRng::seed_from_u64(seed));
+            let tiles = dungeon.tiles();
+
+            let start = dungeon.rooms[0].center();
+            let mut seen = HashSet::from([start]);
+            let mut queue = VecDeque::from([start]);
+            while let Some((x, y)) = queue.pop_front() {
+                assert!(
+                    x > 0 && y > 0 && x < 79 && y < 49,
+                    "walked out at {:?}",
+                    (x, y)
+                );
+                for next in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                    if tiles.is_walkable(next) && seen.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            for room in &dungeon.rooms {
+                assert!(
+                    seen.contains(&room.center()),
+                    "seed {} left a room cut off",
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_blocked_corridor_falls_back_to_the_next_closest_room() {
+        let mut dungeon = Dungeon {
+            width: 40,
+            height: 30,
+            rooms: vec![
+                Room::closed(1, 1, 6, 6),
+                Room::closed(1, 14, 6, 6),
+                // Walls off the room below from the one above
+                Room::closed(1, 8, 38, 5),
+                Room::closed(20, 1, 6, 6),
+            ],
+            corridors: HashSet::new(),
+            graph: RoomGraph::default(),
+        };
+
+        dungeon.connect_closest(&[0], &[1, 3]);
+
+        assert_eq!(dungeon.graph.edges, [(0, 3)]);
+    }
+}
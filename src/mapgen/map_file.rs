@@ -0,0 +1,392 @@
+use crate::drawable::fixture::{Fixture, FixtureKind};
+use crate::drawable::Drawable;
+use crate::enemy::goblin::Goblin;
+use crate::mapgen::Level;
+use crate::tile::{Coord, TileKind, TileMap};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// What a glyph in a map file stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    /// Terrain, named like the tile kind. Entities stand on floor.
+    Tile(TileKind),
+    Fungus,
+    /// A torch mounted on a wall, which is a wall tile as well.
+    Sconce,
+    Goblin,
+    /// Where the player starts. A map has exactly one.
+    Start,
+    /// Where the player leaves the level. A map has exactly one.
+    Exit,
+}
+
+impl Feature {
+    /// Every feature a legend can use. Stairs are left out because they are placed at the
+    /// start and the exit.
+    pub const ALL: [Feature; 14] = [
+        Feature::Tile(TileKind::Floor),
+        Feature::Tile(TileKind::Wall),
+        Feature::Tile(TileKind::Door),
+        Feature::Tile(TileKind::Tree),
+        Feature::Tile(TileKind::Water),
+        Feature::Tile(TileKind::DeepWater),
+        Feature::Tile(TileKind::Lava),
+        Feature::Tile(TileKind::Rubble),
+        Feature::Tile(TileKind::Chasm),
+        Feature::Fungus,
+        Feature::Sconce,
+        Feature::Goblin,
+        Feature::Start,
+        Feature::Exit,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Tile(kind) => kind.name(),
+            Feature::Fungus => "fungus",
+            Feature::Sconce => "sconce",
+            Feature::Goblin => "goblin",
+            Feature::Start => "start",
+            Feature::Exit => "exit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.name() == name)
+    }
+}
+
+/// A hand-made level read from a map file.
+///
+/// A map file has a `[legend]` section of `glyph = feature` lines followed by a `[map]` section
+/// holding the level itself, one row of glyphs per line. Spaces in the map are outside the level
+/// and cannot be given a meaning. Before the map section, blank lines and lines starting with
+/// `#` are skipped, unless the line is itself a legend entry such as `# = wall`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapFile {
+    pub path: PathBuf,
+    /// Everything but floor and empty space, by position in the level.
+    pub features: Vec<(Coord, Feature)>,
+    pub start: Coord,
+    pub exit: Coord,
+}
+
+impl MapFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MapError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(MapError::Io)?;
+
+        let mut map = MapFile::parse(&text)?;
+        map.path = path.to_path_buf();
+        Ok(map)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, MapError> {
+        let mut legend: HashMap<char, Feature> = HashMap::new();
+        let mut in_legend = false;
+        let mut map_line = None;
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+
+            match line {
+                "[legend]" => {
+                    in_legend = true;
+                    continue;
+                }
+                "[map]" => {
+                    map_line = Some(line_number);
+                    break;
+                }
+                _ => {}
+            }
+
+            let entry = line
+                .split_once('=')
+                .filter(|(glyph, _)| glyph.trim().chars().count() == 1);
+            let Some((glyph, name)) = entry else {
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                return Err(MapError::parse(
+                    line_number,
+                    column_of(raw_line, line),
+                    "expected `glyph = feature`",
+                ));
+            };
+
+            if !in_legend {
+                return Err(MapError::parse(
+                    line_number,
+                    column_of(raw_line, line),
+                    "legend entries go after `[legend]`",
+                ));
+            }
+
+            let glyph = glyph.trim().chars().next().unwrap_or(' ');
+            let name = name.trim();
+            let feature = Feature::from_name(name).ok_or_else(|| {
+                let names: Vec<_> = Feature::ALL.iter().map(|feature| feature.name()).collect();
+                MapError::parse(
+                    line_number,
+                    column_of(raw_line, name),
+                    format!("unknown feature `{}`, use {}", name, names.join(", ")),
+                )
+            })?;
+
+            if legend.insert(glyph, feature).is_some() {
+                return Err(MapError::parse(
+                    line_number,
+                    column_of(raw_line, line),
+                    format!("`{}` is already in the legend", glyph),
+                ));
+            }
+        }
+
+        let Some(map_line) = map_line else {
+            return Err(MapError::parse(
+                text.lines().count().max(1),
+                1,
+                "missing `[map]` section",
+            ));
+        };
+
+        let mut map = MapFile {
+            path: PathBuf::new(),
+            features: Vec::new(),
+            start: (0, 0),
+            exit: (0, 0),
+        };
+        let mut start = None;
+        let mut exit = None;
+        let mut rows: Vec<&str> = text.lines().skip(map_line).map(str::trim_end).collect();
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, glyph) in row.chars().enumerate() {
+                if glyph == ' ' {
+                    continue;
+                }
+
+                let position = (x as i32, y as i32);
+                let (line, column) = (map_line + 1 + y, x + 1);
+                let feature = *legend.get(&glyph).ok_or_else(|| {
+                    MapError::parse(line, column, format!("`{}` is not in the legend", glyph))
+                })?;
+
+                let found = match feature {
+                    Feature::Tile(TileKind::Floor) => continue,
+                    Feature::Start => &mut start,
+                    Feature::Exit => &mut exit,
+                    _ => {
+                        map.features.push((position, feature));
+                        continue;
+                    }
+                };
+
+                if found.replace(position).is_some() {
+                    return Err(MapError::parse(
+                        line,
+                        column,
+                        format!("a map has only one {}", feature.name()),
+                    ));
+                }
+            }
+        }
+
+        let missing = |feature: Feature| {
+            MapError::parse(map_line, 1, format!("the map has no {}", feature.name()))
+        };
+        map.start = start.ok_or_else(|| missing(Feature::Start))?;
+        map.exit = exit.ok_or_else(|| missing(Feature::Exit))?;
+
+        if let Some((x, y)) = map.leak(&rows) {
+            return Err(MapError::parse(
+                map_line + 1 + y as usize,
+                x as usize + 1,
+                "the level is open here, so the player could walk out of it",
+            ));
+        }
+
+        Ok(map)
+    }
+
+    /// Builds the level. Every call gives a fresh one with all its goblins back.
+    pub fn level(&self) -> Level {
+        let mut tiles = TileMap::new();
+        let mut drawables: Vec<Box<dyn Drawable>> = Vec::new();
+
+        for &((x, y), feature) in &self.features {
+            let drawable: Box<dyn Drawable> = match feature {
+                Feature::Tile(kind) => {
+                    tiles.set((x, y), kind);
+                    continue;
+                }
+                Feature::Sconce => {
+                    tiles.set((x, y), TileKind::Wall);
+                    Box::new(Fixture::new(x, y, FixtureKind::Sconce))
+                }
+                Feature::Fungus => Box::new(Fixture::new(x, y, FixtureKind::Fungus)),
+                Feature::Goblin => Box::new(Goblin::new(x, y)),
+                Feature::Start | Feature::Exit => continue,
+            };
+            drawables.push(drawable);
+        }
+
+        Level {
+            tiles,
+            drawables,
+            start: self.start,
+            exit: self.exit,
+        }
+    }
+
+    /// The first tile the player can reach from the start that is next to empty space, if
+    /// there is one. The player moves diagonally too, so a gap between two walls that only
+    /// touch at a corner is a way out.
+    fn leak(&self, rows: &[&str]) -> Option<Coord> {
+        let tiles = self.level().tiles;
+
+        let is_outside = |(x, y): Coord| {
+            y < 0
+                || x < 0
+                || rows
+                    .get(y as usize)
+                    .and_then(|row| row.chars().nth(x as usize))
+                    .is_none_or(|glyph| glyph == ' ')
+        };
+
+        let mut seen = HashSet::from([self.start]);
+        let mut queue = VecDeque::from([self.start]);
+        while let Some((x, y)) = queue.pop_front() {
+            let neighbours = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)));
+            for next in neighbours {
+                if !tiles.is_walkable(next) || !seen.insert(next) {
+                    continue;
+                }
+                if is_outside(next) {
+                    return Some((x, y));
+                }
+                queue.push_back(next);
+            }
+        }
+
+        None
+    }
+}
+
+/// The column the last `part` in `line` starts at, counting from 1.
+fn column_of(line: &str, part: &str) -> usize {
+    line.rfind(part)
+        .map_or(1, |offset| line[..offset].chars().count() + 1)
+}
+
+#[derive(Debug)]
+pub enum MapError {
+    Io(std::io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl MapError {
+    fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        MapError::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Io(err) => write!(f, "could not read map: {}", err),
+            MapError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGEND: &str = "[legend]\n# = wall\n. = floor\n@ = start\n> = exit\ng = goblin\n";
+
+    #[test]
+    fn test_example_map_loads() {
+        let map = MapFile::parse(include_str!("../../maps/crossroads.map")).unwrap();
+        let goblins = map
+            .features
+            .iter()
+            .filter(|(_, feature)| *feature == Feature::Goblin)
+            .count();
+
+        assert_ne!(map.start, map.exit);
+        assert!(goblins > 0);
+        assert_eq!(map.level().start, map.start);
+    }
+
+    #[test]
+    fn test_map_places_features_by_row_and_column() {
+        let text = format!("{}[map]\n#####\n#@g>#\n#####\n", LEGEND);
+        let map = MapFile::parse(&text).unwrap();
+
+        assert_eq!(map.start, (1, 1));
+        assert_eq!(map.exit, (3, 1));
+        assert!(map.features.contains(&((2, 1), Feature::Goblin)));
+        assert!(map
+            .features
+            .contains(&((0, 2), Feature::Tile(TileKind::Wall))));
+    }
+
+    #[test]
+    fn test_errors_report_line_and_column() {
+        let err = MapFile::parse("[legend]\n# = wall\nx = chair\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 5: unknown feature `chair`, use floor, wall, door, tree, water, \
+             deep-water, lava, rubble, chasm, fungus, sconce, goblin, start, exit"
+        );
+
+        let text = format!("{}[map]\n#####\n#@?>#\n#####\n", LEGEND);
+        let err = MapFile::parse(&text).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 9, column 3: `?` is not in the legend"
+        );
+
+        let text = format!("{}[map]\n#####\n#@.>.\n#####\n", LEGEND);
+        let err = MapFile::parse(&text).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 9, column 5: the level is open here, so the player could walk out of it"
+        );
+    }
+
+    #[test]
+    fn test_diagonal_gaps_leak() {
+        // Nothing stops a diagonal step from the floor right of the start to the blank below
+        // the wall beside it
+        let text = format!("{}[map]\n####\n#@.#\n#>#\n###\n", LEGEND);
+        let err = MapFile::parse(&text).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 9, column 3: the level is open here, so the player could walk out of it"
+        );
+    }
+}
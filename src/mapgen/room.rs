@@ -0,0 +1,129 @@
+use crate::tile::{Coord, TileKind, TileMap};
+
+/// A wall of a room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Left,
+    Bottom,
+    Right,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Top, Side::Left, Side::Bottom, Side::Right];
+
+    /// The step from a door on this side to the tile just outside the room.
+    pub fn outward(self) -> Coord {
+        match self {
+            Side::Top => (0, -1),
+            Side::Left => (-1, 0),
+            Side::Bottom => (0, 1),
+            Side::Right => (1, 0),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Room {
+    pub width: u16,
+    pub height: u16,
+    pub x: i32,
+    pub y: i32,
+    /// Which walls have a door in their middle, indexed by `Side`.
+    pub door_sides: [bool; 4],
+}
+
+impl Room {
+    /// A room with a door in the middle of every wall.
+    pub fn new(x: i32, y: i32, width: u16, height: u16) -> Self {
+        Room {
+            width,
+            height,
+            x,
+            y,
+            door_sides: [true; 4],
+        }
+    }
+
+    /// A room without any doors.
+    pub fn closed(x: i32, y: i32, width: u16, height: u16) -> Self {
+        Room {
+            door_sides: [false; 4],
+            ..Room::new(x, y, width, height)
+        }
+    }
+
+    /// Whether a position is inside the room or part of its walls.
+    pub fn contains(&self, (x, y): Coord) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.width as i32
+            && y < self.y + self.height as i32
+    }
+
+    pub fn center(&self) -> Coord {
+        (
+            self.x + self.width as i32 / 2,
+            self.y + self.height as i32 / 2,
+        )
+    }
+
+    fn is_wall(&self, row: i32, col: i32) -> bool {
+        row == self.y
+            || row == (self.y + self.height as i32 - 1)
+            || col == self.x
+            || col == (self.x + self.width as i32 - 1)
+    }
+
+    fn is_door(&self, row: i32, col: i32) -> bool {
+        Side::ALL
+            .into_iter()
+            .any(|side| self.door_sides[side as usize] && self.door(side) == (col, row))
+    }
+
+    /// The wall that faces towards a position, going by the room's centre.
+    pub fn facing(&self, (x, y): Coord) -> Side {
+        let (center_x, center_y) = self.center();
+        let (dx, dy) = (x - center_x, y - center_y);
+
+        if dx.abs() > dy.abs() {
+            if dx < 0 {
+                Side::Left
+            } else {
+                Side::Right
+            }
+        } else if dy < 0 {
+            Side::Top
+        } else {
+            Side::Bottom
+        }
+    }
+
+    /// Where the door on a side goes, in the middle of the wall, whether or not it has one.
+    pub fn door(&self, side: Side) -> Coord {
+        let middle_x = self.x + (self.width as f32 / 2.0).floor() as i32;
+        let middle_y = self.y + (self.height as f32 / 2.0).floor() as i32;
+        let right = self.x + self.width as i32 - 1;
+        let bottom = self.y + self.height as i32 - 1;
+
+        match side {
+            Side::Top => (middle_x, self.y),
+            Side::Left => (self.x, middle_y),
+            Side::Bottom => (middle_x, bottom),
+            Side::Right => (right, middle_y),
+        }
+    }
+
+    /// Lays the room's walls and doors into a tile map. The floor inside is left alone.
+    pub fn carve(&self, tiles: &mut TileMap) {
+        for row in self.y..(self.y + self.height as i32) {
+            for col in self.x..(self.x + self.width as i32) {
+                if self.is_door(row, col) {
+                    tiles.set((col, row), TileKind::Door);
+                } else if self.is_wall(row, col) {
+                    tiles.set((col, row), TileKind::Wall);
+                }
+            }
+        }
+    }
+}
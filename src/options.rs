@@ -0,0 +1,141 @@
+use crate::camera::CameraMode;
+use crate::game::GameMode;
+use crate::glyphs::{self, GlyphSet};
+use crate::keymap::Keymap;
+use crate::mapgen::LevelKind;
+use crate::theme::{ColorDepth, Theme};
+use std::path::PathBuf;
+
+/// Where the game is saved unless `--save` says otherwise.
+const DEFAULT_SAVE_PATH: &str = "cave-diver.save";
+
+/// Options holds the settings chosen on the command line.
+#[derive(Clone)]
+pub struct Options {
+    /// Run without a terminal and print the final screen as text.
+    pub headless: bool,
+    pub glyphs: &'static GlyphSet,
+    pub theme: Theme,
+    pub color_depth: ColorDepth,
+    pub camera_mode: CameraMode,
+    /// Keep the camera from scrolling past the edges of the level.
+    pub clamp_camera: bool,
+    pub mode: GameMode,
+    pub keymap: Keymap,
+    /// The file the pause menu saves to and the title screen continues from.
+    pub save_path: PathBuf,
+    /// Seed for new games, or `None` for a random one each time.
+    pub seed: Option<u64>,
+    /// The kind of level new games are played on.
+    pub level: LevelKind,
+}
+
+impl Options {
+    /// Parses options from the process arguments.
+    pub fn from_args() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options {
+            headless: false,
+            glyphs: &glyphs::UNICODE,
+            theme: Theme::default(),
+            color_depth: ColorDepth::detect(),
+            camera_mode: CameraMode::Centered,
+            clamp_camera: false,
+            mode: GameMode::RealTime,
+            keymap: Keymap::default(),
+            save_path: PathBuf::from(DEFAULT_SAVE_PATH),
+            seed: None,
+            level: LevelKind::Cave,
+        };
+
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => options.headless = true,
+                "--ascii" => options.glyphs = &glyphs::ASCII,
+                "--theme" => {
+                    let spec = args.next().ok_or("--theme needs a theme name or file")?;
+                    options.theme = Theme::resolve(&spec).map_err(|err| {
+                        format!(
+                            "could not load theme `{}` ({}); built-in themes are {}",
+                            spec,
+                            err,
+                            Theme::BUILTIN.join(", ")
+                        )
+                    })?;
+                }
+                "--colors" => {
+                    let name = args.next().ok_or("--colors needs a color depth")?;
+                    options.color_depth = ColorDepth::from_name(&name).ok_or_else(|| {
+                        format!("unknown color depth `{}`, use truecolor, 256, 16 or mono", name)
+                    })?;
+                }
+                "--camera" => {
+                    let name = args.next().ok_or("--camera needs a camera mode")?;
+                    options.camera_mode = CameraMode::from_name(&name).ok_or_else(|| {
+                        format!(
+                            "unknown camera mode `{}`, use center, deadzone or smooth",
+                            name
+                        )
+                    })?;
+                }
+                "--clamp-camera" => options.clamp_camera = true,
+                "--mode" => {
+                    let name = args.next().ok_or("--mode needs a game mode")?;
+                    options.mode = GameMode::from_name(&name).ok_or_else(|| {
+                        format!("unknown game mode `{}`, use realtime or turn-based", name)
+                    })?;
+                }
+                "--keys" => {
+                    let spec = args.next().ok_or("--keys needs a preset name or file")?;
+                    options.keymap = Keymap::resolve(&spec).map_err(|err| {
+                        format!(
+                            "could not load keymap `{}` ({}); presets are {}",
+                            spec,
+                            err,
+                            Keymap::PRESETS.join(", ")
+                        )
+                    })?;
+                }
+                "--save" => {
+                    let path = args.next().ok_or("--save needs a file")?;
+                    options.save_path = PathBuf::from(path);
+                }
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed needs a number")?;
+                    options.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("invalid seed `{}`, use a whole number", seed))?,
+                    );
+                }
+                "--level" => {
+                    let spec = args.next().ok_or("--level needs a level kind or map file")?;
+                    options.level = LevelKind::from_spec(&spec).map_err(|err| {
+                        format!(
+                            "could not load level `{}` ({}); use cave, dungeon, demo or a map file",
+                            spec, err
+                        )
+                    })?;
+                }
+                _ => return Err(format!("unknown argument `{}`", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_arguments_are_rejected() {
+        let err = Options::parse(["--headless".to_string(), "--fast".to_string()]).err();
+        assert_eq!(err.as_deref(), Some("unknown argument `--fast`"));
+    }
+}
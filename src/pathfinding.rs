@@ -13,6 +13,12 @@ impl Point {
         (self.x - other.x).abs() + (self.y - other.y).abs()
     }
 
+    /// The number of steps between two points when diagonal steps are allowed, so every one of
+    /// the eight surrounding points is 1 away.
+    pub fn chebyshev_distance(&self, other: &Point) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
     pub fn neighbors(&self) -> Vec<Point> {
         vec![
             Point {
@@ -60,6 +66,16 @@ pub fn find_path(
     start: Point,
     goal: Point,
     is_walkable: impl Fn(Point) -> bool,
+) -> Option<Vec<Point>> {
+    find_weighted_path(start, goal, |point| is_walkable(point).then_some(1))
+}
+
+/// Finds the cheapest path where stepping onto a point costs what `cost` says, or `None` when
+/// the point cannot be walked on.
+pub fn find_weighted_path(
+    start: Point,
+    goal: Point,
+    cost: impl Fn(Point) -> Option<i32>,
 ) -> Option<Vec<Point>> {
     let mut open_set = BinaryHeap::new();
     let mut came_from: HashMap<Point, Point> = HashMap::new();
@@ -78,11 +94,11 @@ pub fn find_path(
         }
 
         for neighbor in current.point.neighbors() {
-            if !is_walkable(neighbor) {
+            let Some(step_cost) = cost(neighbor) else {
                 continue;
-            }
+            };
 
-            let tentative_g_score = g_scores[&current.point] + 1;
+            let tentative_g_score = g_scores[&current.point] + step_cost;
 
             if !g_scores.contains_key(&neighbor) || tentative_g_score < g_scores[&neighbor] {
                 came_from.insert(neighbor, current.point);
@@ -159,6 +175,24 @@ mod tests {
         assert_eq!(path.len(), 5);
     }
 
+    #[test]
+    fn test_weighted_path_goes_around_costly_points() {
+        let start = Point { x: 0, y: 1 };
+        let goal = Point { x: 2, y: 1 };
+
+        // The direct route through (1, 1) costs more than the detour over the top.
+        let cost = |point: Point| match (point.x, point.y) {
+            (1, 1) => Some(5),
+            (_, y) if !(0..=2).contains(&y) => None,
+            _ => Some(1),
+        };
+
+        let path = find_weighted_path(start, goal, cost).unwrap();
+
+        assert_eq!(path.len(), 5);
+        assert!(!path.contains(&Point { x: 1, y: 1 }));
+    }
+
     #[test]
     fn test_bounding_box_for_path_with_none() {
         let origin = Point { x: 5, y: 5 };
@@ -1,13 +1,25 @@
-use crate::camera::Camera;
-use crate::frame::Frame;
 use crate::health::Health;
-use crate::tile::{Coord, Tile};
-use std::collections::HashMap;
+use crate::lighting::Torch;
+use crate::scheduler::{self, Energy};
+use crate::tile::TileMap;
+
+/// Seconds a fresh torch burns for.
+const TORCH_FUEL: f32 = 300.0;
+const TORCH_RADIUS: i32 = 8;
+
+const STARTING_POTIONS: u32 = 3;
+
+/// Health a potion restores.
+pub const POTION_HEALING: i32 = 25;
 
 pub struct Player {
     pub x: i32,
     pub y: i32,
     pub health: Health,
+    pub torch: Torch,
+    /// Only used by the turn-based scheduler; in real-time mode the player acts on every key.
+    pub energy: Energy,
+    pub potions: u32,
 }
 
 impl Player {
@@ -16,23 +28,34 @@ impl Player {
             x,
             y,
             health: Health::new(100),
+            torch: Torch::new(TORCH_FUEL, TORCH_RADIUS),
+            energy: Energy::ready(scheduler::SPEED_NORMAL),
+            potions: STARTING_POTIONS,
         }
     }
 
-    pub fn attempt_move(&mut self, dx: i32, dy: i32, static_map: &HashMap<Coord, Tile>) -> bool {
+    /// Drinks a potion if there is one left. Returns whether there was.
+    pub fn drink_potion(&mut self) -> bool {
+        if self.potions == 0 {
+            return false;
+        }
+
+        self.potions -= 1;
+        self.health.heal(POTION_HEALING);
+        true
+    }
+
+    pub fn attempt_move(&mut self, dx: i32, dy: i32, static_map: &TileMap) -> bool {
         let new_x = self.x + dx;
         let new_y = self.y + dy;
 
-        // Static map only stores non-walkable tiles, therefore if the tile is not in the map, it is walkable
-        if let Some(tile) = static_map.get(&(new_x, new_y)) {
-            if !tile.is_walkable() {
-                return false;
-            }
+        if !static_map.is_walkable((new_x, new_y)) {
+            return false;
         }
 
         self.x = new_x;
         self.y = new_y;
 
-        return true;
+        true
     }
 }
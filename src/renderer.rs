@@ -0,0 +1,238 @@
+use crate::frame::{Cell, Frame, CONTINUATION};
+use crate::theme::ColorDepth;
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::style::{
+    Attribute, Attributes, Color, Print, ResetColor, SetAttribute, SetAttributes,
+    SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{Clear, ClearType};
+use std::io::{Result, Write};
+
+/// Renderer presents frames to a terminal, only repainting the cells that changed since the
+/// previously presented frame. Colors are downgraded to what the terminal can display.
+pub struct Renderer {
+    previous: Vec<Cell>,
+    width: u16,
+    height: u16,
+    color_depth: ColorDepth,
+}
+
+impl Renderer {
+    pub fn new(color_depth: ColorDepth) -> Self {
+        Renderer {
+            previous: Vec::new(),
+            width: 0,
+            height: 0,
+            color_depth,
+        }
+    }
+
+    /// Queues the difference between the previous frame and `frame` and flushes it in one write.
+    pub fn render<W: Write>(&mut self, out: &mut W, frame: &Frame) -> Result<()> {
+        let full_repaint = self.previous.len() != frame.buffer.len()
+            || self.width != frame.width
+            || self.height != frame.height;
+
+        if full_repaint {
+            queue!(out, ResetColor, Clear(ClearType::All))?;
+        }
+
+        let mut fg: Option<Color> = None;
+        let mut bg: Option<Color> = None;
+        let mut attrs = Attributes::none();
+        let mut run = String::new();
+
+        for y in 0..frame.height {
+            let row_start = y as usize * frame.width as usize;
+            let mut x = 0;
+
+            while x < frame.width {
+                let index = row_start + x as usize;
+
+                if !full_repaint && self.previous[index] == frame.buffer[index] {
+                    x += 1;
+                    continue;
+                }
+
+                // Start a new run of changed cells at this position
+                queue!(out, MoveTo(x, y))?;
+
+                while x < frame.width {
+                    let index = row_start + x as usize;
+                    let cell = &frame.buffer[index];
+
+                    if !full_repaint && self.previous[index] == *cell {
+                        break;
+                    }
+
+                    if cell.fg != fg || cell.bg != bg || cell.attrs != attrs {
+                        Self::flush_run(out, &mut run)?;
+
+                        if queue_attributes(out, attrs, cell.attrs)? {
+                            fg = None;
+                            bg = None;
+                        }
+                        attrs = cell.attrs;
+
+                        if cell.fg != fg {
+                            queue!(out, SetForegroundColor(self.terminal_foreground(cell.fg)))?;
+                            fg = cell.fg;
+                        }
+                        if cell.bg != bg {
+                            queue!(out, SetBackgroundColor(self.terminal_background(cell.bg)))?;
+                            bg = cell.bg;
+                        }
+                    }
+
+                    // The terminal already moved past this column when it printed the wide
+                    // character on its left.
+                    if cell.ch != CONTINUATION {
+                        run.push(cell.ch);
+                    }
+                    x += 1;
+                }
+
+                Self::flush_run(out, &mut run)?;
+            }
+        }
+
+        if !attrs.is_empty() {
+            queue!(out, SetAttribute(Attribute::Reset))?;
+        } else if fg.is_some() || bg.is_some() {
+            queue!(out, ResetColor)?;
+        }
+
+        out.flush()?;
+
+        self.previous.clone_from(&frame.buffer);
+        self.width = frame.width;
+        self.height = frame.height;
+
+        Ok(())
+    }
+
+    fn terminal_foreground(&self, color: Option<Color>) -> Color {
+        color
+            .and_then(|color| self.color_depth.downgrade_foreground(color))
+            .unwrap_or(Color::Reset)
+    }
+
+    fn terminal_background(&self, color: Option<Color>) -> Color {
+        color
+            .and_then(|color| self.color_depth.downgrade(color))
+            .unwrap_or(Color::Reset)
+    }
+
+    fn flush_run<W: Write>(out: &mut W, run: &mut String) -> Result<()> {
+        if !run.is_empty() {
+            queue!(out, Print(&run))?;
+            run.clear();
+        }
+
+        Ok(())
+    }
+}
+
+/// Queues the commands that switch the terminal from the `from` attributes to `to`. Attributes
+/// that are only added are set directly; removing any requires a full reset, which also resets
+/// the colors, so this returns whether that happened.
+pub(crate) fn queue_attributes<W: Write>(
+    out: &mut W,
+    from: Attributes,
+    to: Attributes,
+) -> Result<bool> {
+    if from == to {
+        return Ok(false);
+    }
+
+    let removed = (from ^ to) & from;
+    if removed.is_empty() {
+        queue!(out, SetAttributes(from ^ to))?;
+        return Ok(false);
+    }
+
+    queue!(out, SetAttribute(Attribute::Reset))?;
+    if !to.is_empty() {
+        queue!(out, SetAttributes(to))?;
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_to_string(renderer: &mut Renderer, frame: &Frame) -> String {
+        let mut out = Vec::new();
+        renderer.render(&mut out, frame).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_unchanged_frame_emits_nothing() {
+        let mut renderer = Renderer::new(ColorDepth::TrueColor);
+        let mut frame = Frame::new(0, 0, 10, 4);
+        frame.draw_text(0, 0, "Hello", None, None, Attributes::none());
+
+        let first = render_to_string(&mut renderer, &frame);
+        assert!(first.contains("Hello"));
+
+        let second = render_to_string(&mut renderer, &frame);
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn test_only_changed_run_is_repainted() {
+        let mut renderer = Renderer::new(ColorDepth::TrueColor);
+        let mut frame = Frame::new(0, 0, 10, 4);
+        frame.draw_text(0, 0, "Hello", None, None, Attributes::none());
+        render_to_string(&mut renderer, &frame);
+
+        frame.draw_text(0, 0, "Jelly", None, None, Attributes::none());
+        let output = render_to_string(&mut renderer, &frame);
+
+        // "H" -> "J" and "o" -> "y" are two separate runs; the unchanged "ell" is skipped.
+        assert!(output.contains('J'));
+        assert!(output.contains('y'));
+        assert!(!output.contains("ell"));
+    }
+
+    #[test]
+    fn test_resize_forces_full_repaint() {
+        let mut renderer = Renderer::new(ColorDepth::TrueColor);
+        let frame = Frame::new(0, 0, 10, 4);
+        render_to_string(&mut renderer, &frame);
+
+        let resized = Frame::new(0, 0, 12, 4);
+        let output = render_to_string(&mut renderer, &resized);
+
+        assert!(output.contains(&" ".repeat(12)));
+    }
+
+    #[test]
+    fn test_attributes_only_emitted_on_change() {
+        let mut renderer = Renderer::new(ColorDepth::TrueColor);
+        let mut frame = Frame::new(0, 0, 10, 1);
+        frame.draw_text(0, 0, "ab", None, None, Attributes::from(Attribute::Bold));
+        frame.draw_text(2, 0, "cd", None, None, Attributes::none());
+
+        let output = render_to_string(&mut renderer, &frame);
+
+        assert_eq!(output.matches("\x1b[1m").count(), 1);
+        assert!(output.contains("\x1b[1mab\x1b[0mcd"));
+    }
+
+    #[test]
+    fn test_colors_are_downgraded_to_terminal_depth() {
+        let mut renderer = Renderer::new(ColorDepth::Ansi16);
+        let mut frame = Frame::new(0, 0, 4, 1);
+        let orange = Color::Rgb { r: 0xE6, g: 0x9F, b: 0x00 };
+        frame.draw_text(0, 0, "x", Some(orange), None, Attributes::none());
+
+        let output = render_to_string(&mut renderer, &frame);
+
+        assert!(output.contains("\x1b[38;5;11mx"));
+    }
+}
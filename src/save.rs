@@ -0,0 +1,319 @@
+use crate::drawable::item::ItemKind;
+use crate::enemy::goblin::GoblinKind;
+use crate::game::GameMode;
+use crate::mapgen::{LevelKind, MAX_DEPTH};
+use crate::tile::Coord;
+use std::fmt;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Version written to new save files. Files with any other version are refused.
+const VERSION: u32 = 3;
+
+/// SaveData is everything a run needs to be picked up again. The level itself is rebuilt, so
+/// only what changes during play is kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveData {
+    /// The seed the level is rebuilt from.
+    pub seed: u64,
+    /// Map files are loaded again from their path.
+    pub level: LevelKind,
+    pub mode: GameMode,
+    /// The level the player is on.
+    pub depth: u32,
+    /// The deepest level the player has been to. Every level down to it is rebuilt.
+    pub deepest: u32,
+    pub turns: u32,
+    pub kills: u32,
+    pub player: Coord,
+    pub health: i32,
+    /// Seconds of torch fuel left.
+    pub torch: f32,
+    pub potions: u32,
+    /// The goblins still alive, on every level.
+    pub goblins: Vec<SavedGoblin>,
+    /// The items not picked up yet, on every level.
+    pub items: Vec<SavedItem>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SavedGoblin {
+    pub depth: u32,
+    pub kind: GoblinKind,
+    pub position: Coord,
+    pub health: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SavedItem {
+    pub depth: u32,
+    pub kind: ItemKind,
+    pub position: Coord,
+    pub amount: u32,
+}
+
+impl SaveData {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SaveError> {
+        let text = std::fs::read_to_string(path).map_err(SaveError::Io)?;
+        SaveData::parse(&text)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        std::fs::write(path, self.to_text()).map_err(SaveError::Io)
+    }
+
+    /// Writes the save as `key = value` lines, with one `goblin = depth kind x y health` line
+    /// per goblin and one `item = depth kind x y amount` line per item.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "version = {}", VERSION);
+        let _ = writeln!(text, "seed = {}", self.seed);
+        let _ = writeln!(text, "level = {}", self.level);
+        let _ = writeln!(text, "mode = {}", self.mode.name());
+        let _ = writeln!(text, "depth = {}", self.depth);
+        let _ = writeln!(text, "deepest = {}", self.deepest);
+        let _ = writeln!(text, "turns = {}", self.turns);
+        let _ = writeln!(text, "kills = {}", self.kills);
+        let _ = writeln!(text, "player = {} {}", self.player.0, self.player.1);
+        let _ = writeln!(text, "health = {}", self.health);
+        let _ = writeln!(text, "torch = {}", self.torch);
+        let _ = writeln!(text, "potions = {}", self.potions);
+
+        for goblin in &self.goblins {
+            let (x, y) = goblin.position;
+            let (depth, kind) = (goblin.depth, goblin.kind.name());
+            let _ = writeln!(text, "goblin = {} {} {} {} {}", depth, kind, x, y, goblin.health);
+        }
+
+        for item in &self.items {
+            let (x, y) = item.position;
+            let (depth, kind) = (item.depth, item.kind.name());
+            let _ = writeln!(text, "item = {} {} {} {} {}", depth, kind, x, y, item.amount);
+        }
+
+        text
+    }
+
+    /// Parses a save written by `to_text`. Blank lines and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> Result<Self, SaveError> {
+        let mut save = SaveData {
+            seed: 0,
+            level: LevelKind::Demo,
+            mode: GameMode::RealTime,
+            depth: 1,
+            deepest: 1,
+            turns: 0,
+            kills: 0,
+            player: (0, 0),
+            health: 0,
+            torch: 0.0,
+            potions: 0,
+            goblins: Vec::new(),
+            items: Vec::new(),
+        };
+        let mut version = None;
+        let mut has_seed = false;
+        let mut has_player = false;
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(SaveError::parse(line_number, "expected `key = value`"));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || SaveError::parse(line_number, format!("invalid {} `{}`", key, value));
+
+            match key {
+                "version" => version = Some(value.parse::<u32>().map_err(|_| invalid())?),
+                "seed" => {
+                    save.seed = value.parse().map_err(|_| invalid())?;
+                    has_seed = true;
+                }
+                "level" => {
+                    save.level = LevelKind::from_spec(value).map_err(|err| {
+                        SaveError::parse(line_number, format!("invalid level `{}`: {}", value, err))
+                    })?
+                }
+                "mode" => save.mode = GameMode::from_name(value).ok_or_else(invalid)?,
+                // Every level down to the deepest is rebuilt on loading, so it has to exist
+                "depth" | "deepest" => {
+                    let depth = value
+                        .parse()
+                        .ok()
+                        .filter(|&depth| depth <= MAX_DEPTH)
+                        .ok_or_else(invalid)?;
+                    match key {
+                        "depth" => save.depth = depth,
+                        _ => save.deepest = depth,
+                    }
+                }
+                "turns" => save.turns = value.parse().map_err(|_| invalid())?,
+                "kills" => save.kills = value.parse().map_err(|_| invalid())?,
+                "player" => {
+                    let [x, y] = parse_numbers(value).ok_or_else(invalid)?;
+                    save.player = (x, y);
+                    has_player = true;
+                }
+                "health" => save.health = value.parse().map_err(|_| invalid())?,
+                "torch" => save.torch = value.parse().map_err(|_| invalid())?,
+                "potions" => save.potions = value.parse().map_err(|_| invalid())?,
+                "goblin" => {
+                    let (depth, kind, [x, y, health]) =
+                        parse_placed(value, GoblinKind::from_name).ok_or_else(invalid)?;
+                    save.goblins.push(SavedGoblin {
+                        depth,
+                        kind,
+                        position: (x, y),
+                        health,
+                    });
+                }
+                "item" => {
+                    let (depth, kind, [x, y, amount]) =
+                        parse_placed(value, ItemKind::from_name).ok_or_else(invalid)?;
+                    save.items.push(SavedItem {
+                        depth,
+                        kind,
+                        position: (x, y),
+                        amount: u32::try_from(amount).map_err(|_| invalid())?,
+                    });
+                }
+                _ => {
+                    return Err(SaveError::parse(
+                        line_number,
+                        format!("unknown key `{}`", key),
+                    ))
+                }
+            }
+        }
+
+        match version {
+            Some(VERSION) => {}
+            Some(other) => return Err(SaveError::Version(other)),
+            None => return Err(SaveError::parse(1, "missing version")),
+        }
+
+        if !has_seed {
+            return Err(SaveError::parse(1, "missing seed"));
+        }
+
+        if !has_player {
+            return Err(SaveError::parse(1, "missing player position"));
+        }
+
+        save.depth = save.depth.max(1);
+        save.deepest = save.deepest.max(save.depth);
+
+        Ok(save)
+    }
+}
+
+/// Parses a `depth kind` pair followed by exactly `N` integers, as goblin and item lines have.
+fn parse_placed<T, const N: usize>(
+    value: &str,
+    kind: impl Fn(&str) -> Option<T>,
+) -> Option<(u32, T, [i32; N])> {
+    let words: Vec<&str> = value.split_whitespace().collect();
+    let [depth, name, numbers @ ..] = words.as_slice() else {
+        return None;
+    };
+    let numbers: Vec<i32> = numbers
+        .iter()
+        .map(|number| number.parse().ok())
+        .collect::<Option<_>>()?;
+
+    Some((depth.parse().ok()?, kind(name)?, numbers.try_into().ok()?))
+}
+
+/// Parses exactly `N` whitespace separated integers.
+fn parse_numbers<const N: usize>(value: &str) -> Option<[i32; N]> {
+    let numbers: Vec<i32> = value
+        .split_whitespace()
+        .map(|number| number.parse().ok())
+        .collect::<Option<_>>()?;
+
+    numbers.try_into().ok()
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    Version(u32),
+}
+
+impl SaveError {
+    fn parse(line: usize, message: impl Into<String>) -> Self {
+        SaveError::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "could not access save: {}", err),
+            SaveError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            SaveError::Version(version) => {
+                write!(f, "save is from version {}, expected {}", version, VERSION)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_round_trips() {
+        let save = SaveData {
+            seed: 1234,
+            level: LevelKind::Cave,
+            mode: GameMode::TurnBased,
+            depth: 2,
+            deepest: 3,
+            turns: 42,
+            kills: 3,
+            player: (-3, 7),
+            health: 63,
+            torch: 120.5,
+            potions: 1,
+            goblins: vec![SavedGoblin {
+                depth: 3,
+                kind: GoblinKind::Brute,
+                position: (20, 11),
+                health: 4,
+            }],
+            items: vec![SavedItem {
+                depth: 1,
+                kind: ItemKind::Oil,
+                position: (-2, 5),
+                amount: 30,
+            }],
+        };
+
+        assert_eq!(SaveData::parse(&save.to_text()).unwrap(), save);
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = SaveData::parse("version = 3\nplayer = 1 2\ngoblin = 1 ogre 1 2 3\n")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "line 3: invalid goblin `1 ogre 1 2 3`");
+
+        let err = SaveData::parse("version = 9\nseed = 1\nplayer = 1 2\n").unwrap_err();
+        assert_eq!(err.to_string(), "save is from version 9, expected 3");
+
+        let err = SaveData::parse("version = 3\nseed = 1\nplayer = 1 2\ndeepest = 4000000000\n")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "line 4: invalid deepest `4000000000`");
+    }
+}
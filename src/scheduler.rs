@@ -0,0 +1,158 @@
+/// Energy an actor needs before it can act.
+pub const ACTION_THRESHOLD: i32 = 100;
+
+/// Speed of an ordinary actor, which gains enough energy for one ordinary action every ten
+/// ticks.
+pub const SPEED_NORMAL: i32 = 10;
+pub const SPEED_FAST: i32 = 20;
+pub const SPEED_SLOW: i32 = 5;
+
+/// Scheduler ticks per second of real time, used to turn elapsed time into energy in real-time
+/// mode.
+pub const TICKS_PER_SECOND: f32 = 30.0;
+
+/// Something an actor spends its energy on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Move,
+    Attack,
+    Wait,
+    DrinkPotion,
+}
+
+impl Action {
+    /// The energy the action uses up.
+    pub fn cost(self) -> i32 {
+        match self {
+            Action::Move => 100,
+            Action::Attack => 100,
+            Action::Wait => 50,
+            Action::DrinkPotion => 150,
+        }
+    }
+}
+
+/// Energy tracks how soon an actor gets to act. It gains `speed` energy every tick and can act
+/// once it has at least `ACTION_THRESHOLD`, so a fast actor acts more often than a slow one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Energy {
+    pub speed: i32,
+    pub energy: i32,
+}
+
+impl Energy {
+    /// Energy for an actor that can act straight away.
+    pub fn ready(speed: i32) -> Self {
+        Energy {
+            speed,
+            energy: ACTION_THRESHOLD,
+        }
+    }
+
+    pub fn can_act(&self) -> bool {
+        self.energy >= ACTION_THRESHOLD
+    }
+
+    pub fn spend(&mut self, action: Action) {
+        self.energy -= action.cost();
+    }
+
+    /// Spends the energy for an action that takes `times` as long as usual, such as a step
+    /// through water.
+    pub fn spend_times(&mut self, action: Action, times: i32) {
+        self.energy -= action.cost() * times;
+    }
+
+    /// Gains the energy for `ticks` ticks, which may be fractional in real-time mode.
+    pub fn gain(&mut self, ticks: f32) {
+        self.energy += (self.speed as f32 * ticks).round() as i32;
+    }
+}
+
+/// Picks the actor that acts next, advancing time one tick at a time until one of them has
+/// enough energy. When several are ready the one with the most energy goes first, then the
+/// earliest in `actors`. Returns its index and the number of ticks that passed, or `None` if
+/// no actor can ever act.
+pub fn next_actor(actors: &mut [&mut Energy]) -> Option<(usize, u32)> {
+    if actors.iter().all(|actor| actor.speed <= 0) {
+        return None;
+    }
+
+    let mut ticks = 0;
+
+    loop {
+        let ready = actors
+            .iter()
+            .enumerate()
+            .filter(|(_, actor)| actor.can_act())
+            .max_by_key(|(index, actor)| (actor.energy, std::cmp::Reverse(*index)));
+
+        if let Some((index, _)) = ready {
+            return Some((index, ticks));
+        }
+
+        for actor in actors.iter_mut() {
+            actor.energy += actor.speed;
+        }
+        ticks += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the scheduler for `turns` actions and returns who acted in order.
+    fn order(actors: &mut [Energy], turns: usize) -> Vec<usize> {
+        let mut order = Vec::new();
+
+        for _ in 0..turns {
+            let mut energies: Vec<&mut Energy> = actors.iter_mut().collect();
+            let (index, _) = next_actor(&mut energies).unwrap();
+            actors[index].spend(Action::Move);
+            order.push(index);
+        }
+
+        order
+    }
+
+    #[test]
+    fn test_fast_actor_acts_twice_as_often() {
+        let mut actors = [
+            Energy {
+                speed: SPEED_NORMAL,
+                energy: 0,
+            },
+            Energy {
+                speed: SPEED_FAST,
+                energy: 0,
+            },
+        ];
+
+        let order = order(&mut actors, 6);
+
+        assert_eq!(order.iter().filter(|&&index| index == 1).count(), 4);
+        assert_eq!(order.iter().filter(|&&index| index == 0).count(), 2);
+    }
+
+    #[test]
+    fn test_cheap_actions_come_back_sooner() {
+        let mut waiting = Energy::ready(SPEED_NORMAL);
+        waiting.spend(Action::Wait);
+        let mut moving = Energy::ready(SPEED_NORMAL);
+        moving.spend(Action::Move);
+
+        let (index, ticks) = next_actor(&mut [&mut waiting, &mut moving]).unwrap();
+
+        assert_eq!((index, ticks), (0, 5));
+    }
+
+    #[test]
+    fn test_no_actor_with_speed_never_acts() {
+        let mut still = Energy {
+            speed: 0,
+            energy: 0,
+        };
+        assert_eq!(next_actor(&mut [&mut still]), None);
+    }
+}
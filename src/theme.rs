@@ -0,0 +1,621 @@
+use crossterm::style::Color;
+use std::fmt;
+use std::path::Path;
+
+/// Semantic color roles that drawables ask the theme for instead of hard-coding colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Wall,
+    Door,
+    Floor,
+    Tree,
+    Sconce,
+    Fungus,
+    Lava,
+    Water,
+    DeepWater,
+    Chasm,
+    Stairs,
+    Item,
+    Remembered,
+    Player,
+    Enemy,
+    EnemyHealth,
+    DebugPath,
+    Damage,
+    Heal,
+    LogInfo,
+    LogWarning,
+    UiText,
+    UiAccent,
+}
+
+impl Role {
+    pub const ALL: [Role; 23] = [
+        Role::Wall,
+        Role::Door,
+        Role::Floor,
+        Role::Tree,
+        Role::Sconce,
+        Role::Fungus,
+        Role::Lava,
+        Role::Water,
+        Role::DeepWater,
+        Role::Chasm,
+        Role::Stairs,
+        Role::Item,
+        Role::Remembered,
+        Role::Player,
+        Role::Enemy,
+        Role::EnemyHealth,
+        Role::DebugPath,
+        Role::Damage,
+        Role::Heal,
+        Role::LogInfo,
+        Role::LogWarning,
+        Role::UiText,
+        Role::UiAccent,
+    ];
+
+    /// The name used for this role in theme files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Wall => "wall",
+            Role::Door => "door",
+            Role::Floor => "floor",
+            Role::Tree => "tree",
+            Role::Sconce => "sconce",
+            Role::Fungus => "fungus",
+            Role::Lava => "lava",
+            Role::Water => "water",
+            Role::DeepWater => "deep-water",
+            Role::Chasm => "chasm",
+            Role::Stairs => "stairs",
+            Role::Item => "item",
+            Role::Remembered => "remembered",
+            Role::Player => "player",
+            Role::Enemy => "enemy",
+            Role::EnemyHealth => "enemy-health",
+            Role::DebugPath => "debug-path",
+            Role::Damage => "damage",
+            Role::Heal => "heal",
+            Role::LogInfo => "log-info",
+            Role::LogWarning => "log-warning",
+            Role::UiText => "ui-text",
+            Role::UiAccent => "ui-accent",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Role> {
+        Role::ALL.into_iter().find(|role| role.name() == name)
+    }
+}
+
+/// The number of colors a terminal can display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    Monochrome,
+}
+
+impl ColorDepth {
+    /// Detects the color depth of the current terminal from the environment.
+    pub fn detect() -> Self {
+        Self::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+            std::env::var_os("NO_COLOR").is_some(),
+        )
+    }
+
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>, no_color: bool) -> Self {
+        if no_color || term == Some("dumb") {
+            return ColorDepth::Monochrome;
+        }
+
+        if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+            return ColorDepth::TrueColor;
+        }
+
+        match term {
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+            "256" => Some(ColorDepth::Ansi256),
+            "16" => Some(ColorDepth::Ansi16),
+            "mono" | "monochrome" => Some(ColorDepth::Monochrome),
+            _ => None,
+        }
+    }
+
+    /// Converts a color into the closest one this depth can display. Monochrome terminals get no
+    /// color at all.
+    pub fn downgrade(self, color: Color) -> Option<Color> {
+        if color == Color::Reset {
+            return Some(color);
+        }
+
+        match self {
+            ColorDepth::TrueColor => Some(color),
+            ColorDepth::Ansi256 => match color {
+                Color::Rgb { r, g, b } => Some(Color::AnsiValue(rgb_to_ansi256(r, g, b))),
+                _ => Some(color),
+            },
+            ColorDepth::Ansi16 => match color {
+                Color::Rgb { .. } | Color::AnsiValue(_) => {
+                    to_rgb(color).map(|(r, g, b)| nearest_ansi16(r, g, b))
+                }
+                _ => Some(color),
+            },
+            ColorDepth::Monochrome => None,
+        }
+    }
+
+    /// Like `downgrade`, for the color of a glyph. Sixteen colors have nothing between black and
+    /// dark grey, so a dimly lit glyph that would round down to black is drawn dark grey instead
+    /// of vanishing into the background.
+    pub fn downgrade_foreground(self, color: Color) -> Option<Color> {
+        let downgraded = self.downgrade(color);
+
+        if downgraded == Some(Color::Black) && to_rgb(color) != Some((0, 0, 0)) {
+            Some(Color::DarkGrey)
+        } else {
+            downgraded
+        }
+    }
+}
+
+/// Theme resolves color roles to concrete colors.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    colors: [Option<Color>; Role::ALL.len()],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let mut theme = Theme::empty("default");
+        theme.set(Role::Tree, Some(Color::Green));
+        theme.set(Role::Sconce, Some(Color::Yellow));
+        theme.set(Role::Fungus, Some(Color::Cyan));
+        theme.set(Role::Lava, Some(Color::Red));
+        theme.set(Role::Water, Some(Color::Blue));
+        theme.set(Role::DeepWater, Some(Color::DarkBlue));
+        theme.set(Role::Chasm, Some(Color::DarkGrey));
+        theme.set(Role::Stairs, Some(Color::Yellow));
+        theme.set(Role::Item, Some(Color::Magenta));
+        theme.set(Role::Remembered, Some(Color::DarkGrey));
+        theme.set(Role::EnemyHealth, Some(Color::Blue));
+        theme.set(Role::DebugPath, Some(Color::Yellow));
+        theme.set(Role::Damage, Some(Color::Red));
+        theme.set(Role::Heal, Some(Color::Green));
+        theme.set(Role::LogInfo, Some(Color::White));
+        theme.set(Role::LogWarning, Some(Color::Yellow));
+        theme.set(Role::UiAccent, Some(Color::Cyan));
+        theme
+    }
+}
+
+impl Theme {
+    pub const BUILTIN: [&'static str; 3] = ["default", "colorblind", "monochrome"];
+
+    fn empty(name: &str) -> Self {
+        Theme {
+            name: name.to_string(),
+            colors: [None; Role::ALL.len()],
+        }
+    }
+
+    /// A palette built from the Okabe-Ito colors, which stay distinguishable with the common
+    /// forms of color blindness.
+    pub fn colorblind() -> Self {
+        let orange = Color::Rgb { r: 0xE6, g: 0x9F, b: 0x00 };
+        let sky_blue = Color::Rgb { r: 0x56, g: 0xB4, b: 0xE9 };
+        let bluish_green = Color::Rgb { r: 0x00, g: 0x9E, b: 0x73 };
+        let yellow = Color::Rgb { r: 0xF0, g: 0xE4, b: 0x42 };
+        let blue = Color::Rgb { r: 0x00, g: 0x72, b: 0xB2 };
+        let vermillion = Color::Rgb { r: 0xD5, g: 0x5E, b: 0x00 };
+
+        let mut theme = Theme::empty("colorblind");
+        theme.set(Role::Tree, Some(bluish_green));
+        theme.set(Role::Sconce, Some(yellow));
+        theme.set(Role::Fungus, Some(sky_blue));
+        theme.set(Role::Lava, Some(vermillion));
+        theme.set(Role::Water, Some(sky_blue));
+        theme.set(Role::DeepWater, Some(blue));
+        theme.set(Role::Chasm, Some(Color::DarkGrey));
+        theme.set(Role::Stairs, Some(yellow));
+        theme.set(Role::Item, Some(orange));
+        theme.set(Role::Remembered, Some(Color::DarkGrey));
+        theme.set(Role::Enemy, Some(vermillion));
+        theme.set(Role::EnemyHealth, Some(sky_blue));
+        theme.set(Role::DebugPath, Some(yellow));
+        theme.set(Role::Damage, Some(vermillion));
+        theme.set(Role::Heal, Some(blue));
+        theme.set(Role::LogInfo, Some(Color::White));
+        theme.set(Role::LogWarning, Some(orange));
+        theme.set(Role::UiAccent, Some(sky_blue));
+        theme
+    }
+
+    /// A palette using only shades of grey.
+    pub fn monochrome() -> Self {
+        let mut theme = Theme::empty("monochrome");
+        theme.set(Role::Wall, Some(Color::Grey));
+        theme.set(Role::Door, Some(Color::White));
+        theme.set(Role::Floor, Some(Color::DarkGrey));
+        theme.set(Role::Tree, Some(Color::Grey));
+        theme.set(Role::Sconce, Some(Color::White));
+        theme.set(Role::Fungus, Some(Color::Grey));
+        theme.set(Role::Lava, Some(Color::White));
+        theme.set(Role::Water, Some(Color::Grey));
+        theme.set(Role::DeepWater, Some(Color::White));
+        theme.set(Role::Chasm, Some(Color::DarkGrey));
+        theme.set(Role::Stairs, Some(Color::White));
+        theme.set(Role::Item, Some(Color::Grey));
+        theme.set(Role::Remembered, Some(Color::DarkGrey));
+        theme.set(Role::Player, Some(Color::White));
+        theme.set(Role::Enemy, Some(Color::White));
+        theme.set(Role::EnemyHealth, Some(Color::Grey));
+        theme.set(Role::DebugPath, Some(Color::DarkGrey));
+        theme.set(Role::Damage, Some(Color::White));
+        theme.set(Role::Heal, Some(Color::Grey));
+        theme.set(Role::LogInfo, Some(Color::Grey));
+        theme.set(Role::LogWarning, Some(Color::White));
+        theme.set(Role::UiAccent, Some(Color::White));
+        theme
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::default()),
+            "colorblind" => Some(Theme::colorblind()),
+            "monochrome" => Some(Theme::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Returns the built-in theme called `spec`, or loads it from a file otherwise.
+    pub fn resolve(spec: &str) -> Result<Self, ThemeError> {
+        match Theme::builtin(spec) {
+            Some(theme) => Ok(theme),
+            None => Theme::load(spec),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(ThemeError::Io)?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Theme::parse(&name, &text)
+    }
+
+    /// Parses a theme file. Each non-empty line is `role = color`, `#` starts a comment, and an
+    /// optional `base = <builtin>` line picks the theme that unlisted roles fall back to.
+    pub fn parse(name: &str, text: &str) -> Result<Self, ThemeError> {
+        let mut theme = Theme::default();
+        let mut assignments = Vec::new();
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ThemeError::parse(line_number, "expected `role = color`"));
+            };
+            let (key, value) = (key.trim(), strip_comment(value.trim()));
+
+            if key == "base" {
+                theme = Theme::builtin(value).ok_or_else(|| {
+                    ThemeError::parse(line_number, format!("unknown base theme `{}`", value))
+                })?;
+                continue;
+            }
+
+            let role = Role::from_name(key).ok_or_else(|| {
+                ThemeError::parse(line_number, format!("unknown role `{}`", key))
+            })?;
+            let color = parse_color(value).ok_or_else(|| {
+                ThemeError::parse(line_number, format!("invalid color `{}`", value))
+            })?;
+
+            assignments.push((role, color));
+        }
+
+        for (role, color) in assignments {
+            theme.set(role, color);
+        }
+        theme.name = name.to_string();
+
+        Ok(theme)
+    }
+
+    pub fn color(&self, role: Role) -> Option<Color> {
+        self.colors[role as usize]
+    }
+
+    pub fn set(&mut self, role: Role, color: Option<Color>) {
+        self.colors[role as usize] = color;
+    }
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl ThemeError {
+    fn parse(line: usize, message: impl Into<String>) -> Self {
+        ThemeError::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::Io(err) => write!(f, "could not read theme: {}", err),
+            ThemeError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+/// Removes a trailing `# comment` from a value, keeping the `#` that starts a hex color.
+fn strip_comment(value: &str) -> &str {
+    let search_from = if value.starts_with('#') { 1 } else { 0 };
+
+    match value[search_from..].find('#') {
+        Some(pos) => value[..search_from + pos].trim_end(),
+        None => value,
+    }
+}
+
+/// Parses a color name (`dark_red`, `darkred`), an ANSI index (`0`-`255`), a hex value
+/// (`#rrggbb`) or `none` for the terminal's default color.
+pub fn parse_color(value: &str) -> Option<Option<Color>> {
+    let value = value.to_lowercase().replace(['_', '-', ' '], "");
+
+    if value == "none" || value == "default" {
+        return Some(None);
+    }
+
+    if let Some(hex) = value.strip_prefix('#') {
+        // Checking the digits first also keeps the slicing below on character boundaries
+        if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Some(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        }));
+    }
+
+    if let Ok(index) = value.parse::<u8>() {
+        return Some(Some(Color::AnsiValue(index)));
+    }
+
+    let color = match value.as_str() {
+        "black" => Color::Black,
+        "darkgrey" | "darkgray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "darkred" => Color::DarkRed,
+        "green" => Color::Green,
+        "darkgreen" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "darkyellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "darkblue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "darkmagenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "darkcyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    };
+
+    Some(Some(color))
+}
+
+/// The sixteen ANSI colors with their usual xterm RGB values, in palette order.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (128, 0, 0)),
+    (Color::DarkGreen, (0, 128, 0)),
+    (Color::DarkYellow, (128, 128, 0)),
+    (Color::DarkBlue, (0, 0, 128)),
+    (Color::DarkMagenta, (128, 0, 128)),
+    (Color::DarkCyan, (0, 128, 128)),
+    (Color::Grey, (192, 192, 192)),
+    (Color::DarkGrey, (128, 128, 128)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (0, 0, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Returns the RGB value of a color, or `None` for `Color::Reset`.
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(index) => Some(ansi256_to_rgb(index)),
+        named => ANSI16
+            .iter()
+            .find(|(candidate, _)| *candidate == named)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |value: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (**level as i32 - value as i32).abs())
+            .map(|(i, _)| i as u8)
+            .unwrap()
+    };
+
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+
+    // Greys are better matched by the dedicated grayscale ramp than by the cube
+    let average = ((r as u16 + g as u16 + b as u16) / 3) as u8;
+    let grey = 232 + (average.saturating_sub(8) / 10).min(23);
+
+    if distance((r, g, b), ansi256_to_rgb(grey)) < distance((r, g, b), ansi256_to_rgb(cube)) {
+        grey
+    } else {
+        cube
+    }
+}
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap()
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_color_depth() {
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm"), false),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm-256color"), false),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::from_env(None, Some("xterm"), false), ColorDepth::Ansi16);
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm"), true),
+            ColorDepth::Monochrome
+        );
+    }
+
+    #[test]
+    fn test_downgrade_colors() {
+        let orange = Color::Rgb { r: 0xE6, g: 0x9F, b: 0x00 };
+
+        assert_eq!(ColorDepth::TrueColor.downgrade(orange), Some(orange));
+        assert_eq!(ColorDepth::Ansi256.downgrade(orange), Some(Color::AnsiValue(178)));
+        assert_eq!(ColorDepth::Ansi16.downgrade(orange), Some(Color::Yellow));
+        assert_eq!(
+            ColorDepth::Ansi256.downgrade(Color::Rgb { r: 100, g: 100, b: 100 }),
+            Some(Color::AnsiValue(241))
+        );
+        assert_eq!(ColorDepth::Ansi16.downgrade(Color::Green), Some(Color::Green));
+        assert_eq!(ColorDepth::Monochrome.downgrade(Color::Green), None);
+    }
+
+    #[test]
+    fn test_dim_glyphs_stay_visible_in_16_colors() {
+        use crate::lighting::{self, Light};
+
+        let dim = lighting::shade(Some(Color::Grey), Light::AMBIENT);
+
+        assert_eq!(ColorDepth::Ansi16.downgrade(dim), Some(Color::Black));
+        assert_eq!(
+            ColorDepth::Ansi16.downgrade_foreground(dim),
+            Some(Color::DarkGrey)
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.downgrade_foreground(Color::Black),
+            Some(Color::Black)
+        );
+        assert_eq!(ColorDepth::Monochrome.downgrade_foreground(dim), None);
+    }
+
+    #[test]
+    fn test_parse_theme_file() {
+        let theme = Theme::parse(
+            "mine",
+            "# my theme\nbase = monochrome\n\nenemy = #ff0000 # angry\nlog-info = 250\nplayer = dark_cyan\n",
+        )
+        .unwrap();
+
+        assert_eq!(theme.name, "mine");
+        assert_eq!(theme.color(Role::Enemy), Some(Color::Rgb { r: 255, g: 0, b: 0 }));
+        assert_eq!(theme.color(Role::LogInfo), Some(Color::AnsiValue(250)));
+        assert_eq!(theme.color(Role::Player), Some(Color::DarkCyan));
+        assert_eq!(theme.color(Role::Wall), Some(Color::Grey));
+    }
+
+    #[test]
+    fn test_parse_theme_errors_report_line() {
+        let err = Theme::parse("bad", "wall = grey\nmagma = red\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: unknown role `magma`");
+
+        let err = Theme::parse("bad", "wall = #12345\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid color `#12345`");
+
+        let err = Theme::parse("bad", "wall = #aééb\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid color `#aééb`");
+    }
+
+    #[test]
+    fn test_example_theme_parses() {
+        let theme = Theme::parse("dusk", include_str!("../themes/dusk.theme")).unwrap();
+
+        assert_eq!(theme.color(Role::Enemy), Some(Color::Rgb { r: 0xd2, g: 0x0f, b: 0x39 }));
+        assert_eq!(theme.color(Role::UiText), None);
+    }
+}
@@ -1,18 +1,319 @@
+use crate::bounding_box::BoundingBox;
+use crate::frame::{Cell, Frame};
+use crate::lighting::{Light, LightSource};
+use crate::theme::Role;
+use crossterm::style::Attributes;
 use std::collections::HashMap;
 
 pub type Coord = (i32, i32);
 
+/// Every kind of terrain a level is made of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TileKind {
+    Floor,
+    Wall,
+    Door,
+    Tree,
+    Water,
+    DeepWater,
+    Lava,
+    Rubble,
+    Chasm,
+    StairsUp,
+    StairsDown,
+}
+
+/// What a kind of tile looks like and how it behaves. The tile map draws and answers queries
+/// from the same definition, so the two cannot disagree.
 #[derive(Clone, Copy, Debug)]
-pub struct Tile {
-    is_walkable: bool,
+pub struct TileDef {
+    /// The name used for the kind in map files.
+    pub name: &'static str,
+    pub glyph: char,
+    pub role: Role,
+    pub walkable: bool,
+    /// Whether the tile blocks line of sight and light.
+    pub opaque: bool,
+    /// How many times as much energy stepping onto the tile takes as stepping onto floor.
+    pub move_cost: i32,
+    /// The radius and color of the light the tile gives off, if any.
+    pub light: Option<(i32, Light)>,
+    pub description: &'static str,
+}
+
+/// The definition of every tile kind, in the order of `TileKind::ALL`.
+const TILES: [TileDef; TileKind::ALL.len()] = [
+    TileDef {
+        name: "floor",
+        glyph: '.',
+        role: Role::Floor,
+        walkable: true,
+        opaque: false,
+        move_cost: 1,
+        light: None,
+        description: "Bare stone floor.",
+    },
+    TileDef {
+        name: "wall",
+        glyph: '#',
+        role: Role::Wall,
+        walkable: false,
+        opaque: true,
+        move_cost: 1,
+        light: None,
+        description: "A solid rock wall.",
+    },
+    TileDef {
+        name: "door",
+        glyph: '+',
+        role: Role::Door,
+        walkable: true,
+        opaque: false,
+        move_cost: 1,
+        light: None,
+        description: "An open doorway.",
+    },
+    TileDef {
+        name: "tree",
+        glyph: 'T',
+        role: Role::Tree,
+        walkable: false,
+        opaque: true,
+        move_cost: 1,
+        light: None,
+        description: "A pale tree growing without sunlight.",
+    },
+    TileDef {
+        name: "water",
+        glyph: '~',
+        role: Role::Water,
+        walkable: true,
+        opaque: false,
+        move_cost: 2,
+        light: None,
+        description: "Knee-deep water, slow to wade through.",
+    },
+    TileDef {
+        name: "deep-water",
+        glyph: '≋',
+        role: Role::DeepWater,
+        walkable: false,
+        opaque: false,
+        move_cost: 1,
+        light: None,
+        description: "Water too deep to cross.",
+    },
+    TileDef {
+        name: "lava",
+        glyph: '≈',
+        role: Role::Lava,
+        walkable: false,
+        opaque: false,
+        move_cost: 1,
+        light: Some((3, Light::new(1.0, 0.35, 0.1))),
+        description: "A pool of glowing lava.",
+    },
+    TileDef {
+        name: "rubble",
+        glyph: ',',
+        role: Role::Wall,
+        walkable: true,
+        opaque: false,
+        move_cost: 3,
+        light: None,
+        description: "Loose rubble, slow going underfoot.",
+    },
+    TileDef {
+        name: "chasm",
+        glyph: ':',
+        role: Role::Chasm,
+        walkable: false,
+        opaque: false,
+        move_cost: 1,
+        light: None,
+        description: "A drop into darkness.",
+    },
+    TileDef {
+        name: "stairs-up",
+        glyph: '<',
+        role: Role::Stairs,
+        walkable: true,
+        opaque: false,
+        move_cost: 1,
+        light: None,
+        description: "Stairs leading back up.",
+    },
+    TileDef {
+        name: "stairs-down",
+        glyph: '>',
+        role: Role::Stairs,
+        walkable: true,
+        opaque: false,
+        move_cost: 1,
+        light: None,
+        description: "Stairs leading further down.",
+    },
+];
+
+impl TileKind {
+    pub const ALL: [TileKind; 11] = [
+        TileKind::Floor,
+        TileKind::Wall,
+        TileKind::Door,
+        TileKind::Tree,
+        TileKind::Water,
+        TileKind::DeepWater,
+        TileKind::Lava,
+        TileKind::Rubble,
+        TileKind::Chasm,
+        TileKind::StairsUp,
+        TileKind::StairsDown,
+    ];
+
+    pub fn def(self) -> &'static TileDef {
+        &TILES[self as usize]
+    }
+
+    pub fn name(self) -> &'static str {
+        self.def().name
+    }
+}
+
+/// TileMap holds the terrain of a level. Positions without a tile are open floor, which is not
+/// drawn, so a level has to be closed in by walls or other tiles that cannot be walked on.
+#[derive(Clone, Debug, Default)]
+pub struct TileMap {
+    tiles: HashMap<Coord, TileKind>,
 }
 
-impl Tile {
-    pub fn new(is_walkable: bool) -> Self {
-        Tile { is_walkable }
+impl TileMap {
+    pub fn new() -> Self {
+        TileMap::default()
+    }
+
+    pub fn set(&mut self, position: Coord, kind: TileKind) {
+        self.tiles.insert(position, kind);
+    }
+
+    /// The tile at `position`, which is floor where nothing has been set.
+    pub fn get(&self, position: Coord) -> TileKind {
+        self.tiles
+            .get(&position)
+            .copied()
+            .unwrap_or(TileKind::Floor)
     }
 
-    pub fn is_walkable(&self) -> bool {
-        self.is_walkable
+    pub fn is_walkable(&self, position: Coord) -> bool {
+        self.get(position).def().walkable
+    }
+
+    pub fn is_opaque(&self, position: Coord) -> bool {
+        self.get(position).def().opaque
+    }
+
+    pub fn move_cost(&self, position: Coord) -> i32 {
+        self.get(position).def().move_cost
+    }
+
+    /// Every tile that has been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, TileKind)> + '_ {
+        self.tiles.iter().map(|(&position, &kind)| (position, kind))
+    }
+
+    /// The smallest box holding every tile that has been set.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        let mut positions = self.tiles.keys();
+        let &(x, y) = positions.next()?;
+
+        let mut bounds = BoundingBox {
+            left: x,
+            right: x + 1,
+            top: y,
+            bottom: y + 1,
+        };
+
+        for &(x, y) in positions {
+            bounds.left = bounds.left.min(x);
+            bounds.right = bounds.right.max(x + 1);
+            bounds.top = bounds.top.min(y);
+            bounds.bottom = bounds.bottom.max(y + 1);
+        }
+
+        Some(bounds)
+    }
+
+    /// The light given off by every glowing tile.
+    pub fn lights(&self) -> impl Iterator<Item = LightSource> + '_ {
+        self.iter().filter_map(|(position, kind)| {
+            kind.def().light.map(|(radius, color)| LightSource {
+                position,
+                radius,
+                color,
+            })
+        })
+    }
+
+    /// Draws every tile inside `view` on the frame's current layer.
+    pub fn draw(&self, frame: &mut Frame, view: &BoundingBox) {
+        for (&(x, y), kind) in &self.tiles {
+            if x < view.left || x >= view.right || y < view.top || y >= view.bottom {
+                continue;
+            }
+
+            let def = kind.def();
+            frame.set_world_cell(
+                x,
+                y,
+                Cell {
+                    ch: def.glyph,
+                    fg: frame.color(def.role),
+                    bg: None,
+                    attrs: Attributes::none(),
+                    is_walkable: def.walkable,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_in_kind_order() {
+        let names = [
+            (TileKind::Floor, "floor"),
+            (TileKind::Wall, "wall"),
+            (TileKind::Door, "door"),
+            (TileKind::Tree, "tree"),
+            (TileKind::Water, "water"),
+            (TileKind::DeepWater, "deep-water"),
+            (TileKind::Lava, "lava"),
+            (TileKind::Rubble, "rubble"),
+            (TileKind::Chasm, "chasm"),
+            (TileKind::StairsUp, "stairs-up"),
+            (TileKind::StairsDown, "stairs-down"),
+        ];
+
+        assert_eq!(TileKind::ALL.len(), names.len());
+        for (index, (kind, name)) in names.into_iter().enumerate() {
+            assert_eq!(TileKind::ALL[index], kind);
+            assert_eq!(kind.def().name, name);
+        }
+        assert_eq!(TileKind::Lava.def().glyph, '≈');
+    }
+
+    #[test]
+    fn test_unset_tiles_are_open_floor() {
+        let mut map = TileMap::new();
+        map.set((1, 0), TileKind::Wall);
+        map.set((2, 0), TileKind::Water);
+
+        assert_eq!(map.get((0, 0)), TileKind::Floor);
+        assert!(map.is_walkable((0, 0)));
+        assert!(!map.is_walkable((1, 0)));
+        assert!(map.is_opaque((1, 0)));
+        assert_eq!(map.move_cost((2, 0)), 2);
     }
 }
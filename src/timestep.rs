@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+/// FixedTimestep turns the uneven time between loop iterations into whole simulation ticks of
+/// equal length, carrying the remainder over to the next iteration. The simulation then behaves
+/// the same however fast or slow the terminal is.
+pub struct FixedTimestep {
+    /// The length of one tick.
+    pub tick: Duration,
+    accumulator: Duration,
+    last: Instant,
+    /// Most ticks run by one call to `advance`, so the game skips ahead after a long stall
+    /// instead of spending even longer catching up.
+    max_ticks: u32,
+}
+
+impl FixedTimestep {
+    pub fn new(ticks_per_second: u32, now: Instant) -> Self {
+        FixedTimestep {
+            tick: Duration::from_secs(1) / ticks_per_second.max(1),
+            accumulator: Duration::ZERO,
+            last: now,
+            max_ticks: 8,
+        }
+    }
+
+    /// Adds the time passed since the previous call and returns how many ticks are due.
+    pub fn advance(&mut self, now: Instant) -> u32 {
+        self.accumulator += now.saturating_duration_since(self.last);
+        self.last = now;
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick {
+            self.accumulator -= self.tick;
+            ticks += 1;
+        }
+
+        if ticks > self.max_ticks {
+            self.accumulator = Duration::ZERO;
+            ticks = self.max_ticks;
+        }
+
+        ticks
+    }
+
+    /// How long until the next tick is due.
+    pub fn until_next_tick(&self, now: Instant) -> Duration {
+        let elapsed = self.accumulator + now.saturating_duration_since(self.last);
+        self.tick.saturating_sub(elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remainder_carries_over() {
+        let start = Instant::now();
+        let mut timestep = FixedTimestep::new(10, start);
+
+        assert_eq!(timestep.advance(start + Duration::from_millis(150)), 1);
+        assert_eq!(timestep.advance(start + Duration::from_millis(200)), 1);
+        assert_eq!(timestep.advance(start + Duration::from_millis(290)), 0);
+        assert_eq!(
+            timestep.until_next_tick(start + Duration::from_millis(290)),
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn test_long_stall_is_capped() {
+        let start = Instant::now();
+        let mut timestep = FixedTimestep::new(10, start);
+
+        assert_eq!(timestep.advance(start + Duration::from_secs(60)), 8);
+        assert_eq!(timestep.advance(start + Duration::from_millis(60_050)), 0);
+    }
+}
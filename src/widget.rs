@@ -0,0 +1,49 @@
+pub(crate) mod dialog;
+pub(crate) mod gauge;
+pub(crate) mod list;
+pub(crate) mod minimap;
+pub(crate) mod panel;
+pub(crate) mod table;
+pub(crate) mod text_view;
+
+use crate::frame::Frame;
+use crate::layout::Rect;
+
+/// Widget is a piece of UI that draws itself into a rectangle of a `Frame`.
+pub trait Widget {
+    fn render(&self, area: Rect, frame: &mut Frame);
+}
+
+/// Renders a widget with all drawing clipped to `area`, on top of any clip already in place.
+pub fn render<W: Widget + ?Sized>(widget: &W, area: Rect, frame: &mut Frame) {
+    let previous = frame.clip();
+    let clip = match previous {
+        Some(outer) => outer.intersection(&area),
+        None => area,
+    };
+
+    if clip.is_empty() {
+        return;
+    }
+
+    frame.set_clip(Some(clip));
+    widget.render(area, frame);
+    frame.set_clip(previous);
+}
+
+/// Shortens `text` to at most `width` columns.
+pub fn truncate(frame: &Frame, text: &str, width: u16) -> String {
+    let mut used = 0;
+    let mut result = String::new();
+
+    for ch in text.chars() {
+        let ch_width = frame.text_width(ch.encode_utf8(&mut [0; 4]));
+        if used + ch_width > width {
+            break;
+        }
+        used += ch_width;
+        result.push(ch);
+    }
+
+    result
+}
@@ -0,0 +1,196 @@
+use crate::frame::Frame;
+use crate::layout::Rect;
+use crate::widget::panel::Panel;
+use crate::widget::text_view::TextView;
+use crate::widget::{render, Widget};
+use crossterm::style::{Attribute, Attributes};
+
+/// A modal box centered over the area it is rendered into, with a message and a row of buttons.
+/// Everything beneath it is hidden.
+pub struct Dialog {
+    pub title: String,
+    pub message: String,
+    pub buttons: Vec<String>,
+    pub selected: usize,
+    /// Preferred outer width; the dialog shrinks to fit smaller areas.
+    pub width: u16,
+}
+
+impl Dialog {
+    pub fn new(title: &str, message: &str, buttons: &[&str]) -> Self {
+        Dialog {
+            title: title.to_string(),
+            message: message.to_string(),
+            buttons: buttons.iter().map(|button| button.to_string()).collect(),
+            selected: 0,
+            width: 40,
+        }
+    }
+
+    /// Rows kept for the buttons at the bottom: a blank row and the row of buttons.
+    fn button_rows(&self) -> u16 {
+        if self.buttons.is_empty() {
+            0
+        } else {
+            2
+        }
+    }
+
+    /// The rectangle the dialog occupies when centered in `area`.
+    pub fn area(&self, area: Rect) -> Rect {
+        let width = self.width.min(area.width);
+        // Borders and padding take four columns, and the text view keeps one for its arrows
+        let message_lines = TextView::new(&self.message)
+            .lines(width.saturating_sub(5))
+            .len();
+        let height = (message_lines as u16 + self.button_rows() + 2).min(area.height);
+
+        Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+impl Widget for Dialog {
+    fn render(&self, area: Rect, frame: &mut Frame) {
+        let outer = self.area(area);
+
+        frame.clear_rect(outer);
+        render(&Panel::new(&self.title), outer, frame);
+
+        let inner = Panel::inner(outer);
+        let message = Rect::new(
+            inner.x + 1,
+            inner.y,
+            inner.width.saturating_sub(2),
+            inner.height.saturating_sub(self.button_rows()),
+        );
+        render(&TextView::new(&self.message), message, frame);
+
+        if self.buttons.is_empty() || inner.height == 0 {
+            return;
+        }
+
+        let labels: Vec<String> = self
+            .buttons
+            .iter()
+            .map(|button| format!("[ {} ]", button))
+            .collect();
+        let total_width = labels
+            .iter()
+            .map(|label| frame.text_width(label))
+            .sum::<u16>()
+            + labels.len() as u16
+            - 1;
+
+        let y = inner.bottom() - 1;
+        let mut x = inner.x + inner.width.saturating_sub(total_width) / 2;
+
+        for (i, label) in labels.iter().enumerate() {
+            let attrs = if i == self.selected {
+                Attributes::from(Attribute::Reverse)
+            } else {
+                Attributes::none()
+            };
+
+            frame.draw_text(x, y, label, None, None, attrs);
+            x += frame.text_width(label) + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+    use crate::glyphs;
+
+    #[test]
+    fn test_dialog_covers_what_is_beneath() {
+        let mut frame = Frame::new(0, 0, 24, 7);
+        frame.set_glyphs(&glyphs::ASCII);
+        for y in 0..7 {
+            frame.draw_text(0, y, &"x".repeat(24), None, None, Attributes::none());
+        }
+
+        let mut dialog = Dialog::new("Quit", "Really?", &["Yes", "No"]);
+        dialog.width = 20;
+        dialog.selected = 1;
+        render(&dialog, Rect::new(0, 0, 24, 7), &mut frame);
+
+        assert_eq!(
+            frame_to_text(&frame),
+            [
+                "xxxxxxxxxxxxxxxxxxxxxxxx",
+                "xx+- Quit -----------+xx",
+                "xx| Really?          |xx",
+                "xx|                  |xx",
+                "xx|  [ Yes ] [ No ]  |xx",
+                "xx+------------------+xx",
+                "xxxxxxxxxxxxxxxxxxxxxxxx",
+            ]
+            .join("\n")
+        );
+        let selected = frame.buffer[4 * frame.width as usize + 13];
+        assert!(selected.attrs.has(Attribute::Reverse));
+    }
+
+    #[test]
+    fn test_dialog_is_tall_enough_for_text_that_only_just_wraps() {
+        let mut frame = Frame::new(0, 0, 24, 8);
+        frame.set_glyphs(&glyphs::ASCII);
+        for y in 0..8 {
+            frame.draw_text(0, y, &"x".repeat(24), None, None, Attributes::none());
+        }
+
+        // Sixteen characters fit the padding but not the column the text view keeps free
+        let mut dialog = Dialog::new("Quit", "Really quit now?", &["Yes", "No"]);
+        dialog.width = 20;
+        render(&dialog, Rect::new(0, 0, 24, 8), &mut frame);
+
+        assert_eq!(
+            frame_to_text(&frame),
+            [
+                "xxxxxxxxxxxxxxxxxxxxxxxx",
+                "xx+- Quit -----------+xx",
+                "xx| Really quit      |xx",
+                "xx| now?             |xx",
+                "xx|                  |xx",
+                "xx|  [ Yes ] [ No ]  |xx",
+                "xx+------------------+xx",
+                "xxxxxxxxxxxxxxxxxxxxxxxx",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_clamped_dialog_keeps_its_message_off_the_buttons() {
+        let mut frame = Frame::new(0, 0, 24, 6);
+        frame.set_glyphs(&glyphs::ASCII);
+        for y in 0..6 {
+            frame.draw_text(0, y, &"x".repeat(24), None, None, Attributes::none());
+        }
+
+        let mut dialog = Dialog::new("Quit", "Really quit now? All is lost.", &["Yes", "No"]);
+        dialog.width = 20;
+        render(&dialog, Rect::new(0, 0, 24, 6), &mut frame);
+
+        // The rest of the message is left for scrolling instead of spilling onto the buttons
+        assert_eq!(
+            frame_to_text(&frame),
+            [
+                "xx+- Quit -----------+xx",
+                "xx| Really quit      |xx",
+                "xx| now? All is    v |xx",
+                "xx|                  |xx",
+                "xx|  [ Yes ] [ No ]  |xx",
+                "xx+------------------+xx",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::frame::Frame;
+use crate::layout::Rect;
+use crate::widget::{truncate, Widget};
+use crossterm::style::{Attributes, Color};
+
+/// A horizontal bar filled in proportion to `ratio`, with an optional label centered on it.
+pub struct Gauge {
+    /// How full the bar is, from 0.0 to 1.0.
+    pub ratio: f32,
+    pub label: Option<String>,
+    pub filled_color: Option<Color>,
+    pub empty_color: Option<Color>,
+}
+
+impl Gauge {
+    pub fn new(current: i32, max: i32) -> Self {
+        let ratio = if max > 0 {
+            current as f32 / max as f32
+        } else {
+            0.0
+        };
+
+        Gauge {
+            ratio,
+            label: None,
+            filled_color: None,
+            empty_color: None,
+        }
+    }
+}
+
+impl Widget for Gauge {
+    fn render(&self, area: Rect, frame: &mut Frame) {
+        let glyphs = frame.glyphs();
+        let filled = (self.ratio.clamp(0.0, 1.0) * area.width as f32).round() as u16;
+
+        for y in area.y..area.bottom() {
+            for x in area.x..area.right() {
+                if x - area.x < filled {
+                    frame.set(
+                        x,
+                        y,
+                        glyphs.gauge_filled,
+                        self.filled_color,
+                        None,
+                        Attributes::none(),
+                    );
+                } else {
+                    frame.set(
+                        x,
+                        y,
+                        glyphs.gauge_empty,
+                        self.empty_color,
+                        None,
+                        Attributes::none(),
+                    );
+                }
+            }
+        }
+
+        if let Some(label) = &self.label {
+            let label = truncate(frame, label, area.width);
+            let x = area.x + (area.width - frame.text_width(&label)) / 2;
+            let y = area.y + area.height / 2;
+
+            frame.draw_text(x, y, &label, None, None, Attributes::none());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+    use crate::glyphs;
+    use crate::widget::render;
+
+    #[test]
+    fn test_gauge_fills_in_proportion() {
+        let mut frame = Frame::new(0, 0, 10, 1);
+        frame.set_glyphs(&glyphs::ASCII);
+
+        render(&Gauge::new(3, 10), Rect::new(0, 0, 10, 1), &mut frame);
+
+        assert_eq!(frame_to_text(&frame), "###-------");
+    }
+
+    #[test]
+    fn test_gauge_label_is_centered() {
+        let mut frame = Frame::new(0, 0, 10, 1);
+        frame.set_glyphs(&glyphs::ASCII);
+
+        let mut gauge = Gauge::new(10, 10);
+        gauge.label = Some("HP".to_string());
+        render(&gauge, Rect::new(0, 0, 10, 1), &mut frame);
+
+        assert_eq!(frame_to_text(&frame), "####HP####");
+    }
+}
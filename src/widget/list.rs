@@ -0,0 +1,92 @@
+use crate::frame::Frame;
+use crate::layout::Rect;
+use crate::widget::{truncate, Widget};
+use crossterm::style::{Attribute, Attributes, Color};
+
+/// A vertical list of items, scrolled so the selected item is always visible.
+pub struct List {
+    pub items: Vec<String>,
+    pub selected: Option<usize>,
+    pub fg: Option<Color>,
+    /// Attributes of the selected row.
+    pub highlight: Attributes,
+    /// Drawn in front of the selected item; other items are indented by its width.
+    pub highlight_symbol: String,
+}
+
+impl List {
+    pub fn new(items: Vec<String>) -> Self {
+        List {
+            items,
+            selected: None,
+            fg: None,
+            highlight: Attributes::from(Attribute::Reverse),
+            highlight_symbol: "> ".to_string(),
+        }
+    }
+
+    /// The index of the first item shown when `height` rows are available.
+    pub fn offset(&self, height: u16) -> usize {
+        match self.selected {
+            Some(selected) if selected >= height as usize => selected + 1 - height as usize,
+            _ => 0,
+        }
+    }
+}
+
+impl Widget for List {
+    fn render(&self, area: Rect, frame: &mut Frame) {
+        let offset = self.offset(area.height);
+        let indent = " ".repeat(frame.text_width(&self.highlight_symbol) as usize);
+
+        for (row, (index, item)) in self
+            .items
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let y = area.y + row as u16;
+            let is_selected = self.selected == Some(index);
+
+            let (prefix, attrs) = if is_selected {
+                (self.highlight_symbol.as_str(), self.highlight)
+            } else {
+                (indent.as_str(), Attributes::none())
+            };
+
+            let text = truncate(frame, &format!("{}{}", prefix, item), area.width);
+            let padding = " ".repeat((area.width - frame.text_width(&text)) as usize);
+
+            frame.draw_text(
+                area.x,
+                y,
+                &format!("{}{}", text, padding),
+                self.fg,
+                None,
+                attrs,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+    use crate::widget::render;
+
+    #[test]
+    fn test_list_scrolls_to_selection() {
+        let mut frame = Frame::new(0, 0, 10, 2);
+
+        let mut list = List::new(vec!["one".into(), "two".into(), "three".into()]);
+        list.selected = Some(2);
+        render(&list, Rect::new(0, 0, 10, 2), &mut frame);
+
+        assert_eq!(frame_to_text(&frame), "  two\n> three");
+        let selected = frame.buffer[frame.width as usize + 9];
+        assert!(selected.attrs.has(Attribute::Reverse));
+    }
+}
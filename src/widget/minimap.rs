@@ -0,0 +1,166 @@
+use crate::frame::Frame;
+use crate::layout::Rect;
+use crate::tile::Coord;
+use crate::widget::Widget;
+use crossterm::style::{Attributes, Color};
+use std::collections::HashSet;
+
+/// Number of tiles packed into one cell across and down.
+const TILES_ACROSS: i32 = 2;
+const TILES_DOWN: i32 = 4;
+
+/// The bit of each dot of a braille character, indexed by row then column within the cell.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// A symbol drawn on the minimap over the tile it marks.
+pub struct Marker {
+    pub position: Coord,
+    pub ch: char,
+    pub color: Option<Color>,
+}
+
+/// A downscaled view of the level centered on `center`. Every cell packs a 2x4 block of tiles
+/// into one braille character with a dot for each solid tile, so walls read as outlines.
+/// Markers are drawn on top, later ones over earlier ones. With ASCII glyphs a cell holding any
+/// solid tile is drawn as `#` instead.
+pub struct Minimap<'a> {
+    pub solid: &'a HashSet<Coord>,
+    pub markers: Vec<Marker>,
+    pub center: Coord,
+    pub color: Option<Color>,
+}
+
+impl<'a> Minimap<'a> {
+    pub fn new(solid: &'a HashSet<Coord>, center: Coord) -> Self {
+        Minimap {
+            solid,
+            markers: Vec::new(),
+            center,
+            color: None,
+        }
+    }
+
+    /// The world position at the top left of the first cell.
+    fn origin(&self, area: Rect) -> Coord {
+        (
+            self.center.0 - area.width as i32 * TILES_ACROSS / 2,
+            self.center.1 - area.height as i32 * TILES_DOWN / 2,
+        )
+    }
+
+    fn dots(&self, tile_x: i32, tile_y: i32) -> u32 {
+        let mut dots = 0;
+
+        for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+            for (dx, bit) in row.iter().enumerate() {
+                if self
+                    .solid
+                    .contains(&(tile_x + dx as i32, tile_y + dy as i32))
+                {
+                    dots |= bit;
+                }
+            }
+        }
+
+        dots
+    }
+}
+
+impl Widget for Minimap<'_> {
+    fn render(&self, area: Rect, frame: &mut Frame) {
+        let (origin_x, origin_y) = self.origin(area);
+        let ascii_only = frame.glyphs().ascii_only;
+
+        for row in 0..area.height {
+            for col in 0..area.width {
+                let dots = self.dots(
+                    origin_x + col as i32 * TILES_ACROSS,
+                    origin_y + row as i32 * TILES_DOWN,
+                );
+
+                if dots == 0 {
+                    continue;
+                }
+
+                let ch = if ascii_only {
+                    '#'
+                } else {
+                    char::from_u32(BRAILLE_BLANK + dots).unwrap_or(' ')
+                };
+
+                frame.set(
+                    area.x + col,
+                    area.y + row,
+                    ch,
+                    self.color,
+                    None,
+                    Attributes::none(),
+                );
+            }
+        }
+
+        for marker in &self.markers {
+            let col = (marker.position.0 - origin_x).div_euclid(TILES_ACROSS);
+            let row = (marker.position.1 - origin_y).div_euclid(TILES_DOWN);
+
+            if col < 0 || row < 0 || col >= area.width as i32 || row >= area.height as i32 {
+                continue;
+            }
+
+            frame.set(
+                area.x + col as u16,
+                area.y + row as u16,
+                marker.ch,
+                marker.color,
+                None,
+                Attributes::none(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+    use crate::glyphs;
+    use crate::widget::render;
+
+    #[test]
+    fn test_solid_tiles_become_braille_dots() {
+        let mut frame = Frame::new(0, 0, 2, 1);
+        // The cell on the left covers x 0..2 and y 0..4 when centered on (2, 2)
+        let solid: HashSet<Coord> = [(0, 0), (1, 3)].into_iter().collect();
+
+        render(
+            &Minimap::new(&solid, (2, 2)),
+            Rect::new(0, 0, 2, 1),
+            &mut frame,
+        );
+
+        assert_eq!(frame_to_text(&frame), "\u{2881}");
+    }
+
+    #[test]
+    fn test_markers_are_drawn_over_dots() {
+        let mut frame = Frame::new(0, 0, 4, 2);
+        frame.set_glyphs(&glyphs::ASCII);
+        let solid: HashSet<Coord> = (0..8).map(|x| (x, 0)).collect();
+
+        let mut minimap = Minimap::new(&solid, (4, 4));
+        minimap.markers.push(Marker {
+            position: (5, 1),
+            ch: '@',
+            color: None,
+        });
+        minimap.markers.push(Marker {
+            position: (40, 40),
+            ch: 'G',
+            color: None,
+        });
+        render(&minimap, Rect::new(0, 0, 4, 2), &mut frame);
+
+        assert_eq!(frame_to_text(&frame), "##@#\n");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::frame::Frame;
+use crate::layout::Rect;
+use crate::widget::{truncate, Widget};
+use crossterm::style::{Attribute, Attributes, Color};
+
+/// A bordered box with an optional title set into the top edge.
+pub struct Panel {
+    pub title: Option<String>,
+    pub border_color: Option<Color>,
+}
+
+impl Panel {
+    pub fn new(title: &str) -> Self {
+        Panel {
+            title: Some(title.to_string()),
+            border_color: None,
+        }
+    }
+
+    /// The area left inside the border.
+    pub fn inner(area: Rect) -> Rect {
+        area.inner(1)
+    }
+}
+
+impl Widget for Panel {
+    fn render(&self, area: Rect, frame: &mut Frame) {
+        if area.width < 2 || area.height < 2 {
+            return;
+        }
+
+        let glyphs = frame.glyphs();
+        let fg = self.border_color;
+        let none = Attributes::none();
+        let (right, bottom) = (area.right() - 1, area.bottom() - 1);
+
+        for x in area.x + 1..right {
+            frame.set(x, area.y, glyphs.border_horizontal, fg, None, none);
+            frame.set(x, bottom, glyphs.border_horizontal, fg, None, none);
+        }
+        for y in area.y + 1..bottom {
+            frame.set(area.x, y, glyphs.border_vertical, fg, None, none);
+            frame.set(right, y, glyphs.border_vertical, fg, None, none);
+        }
+
+        frame.set(area.x, area.y, glyphs.border_top_left, fg, None, none);
+        frame.set(right, area.y, glyphs.border_top_right, fg, None, none);
+        frame.set(area.x, bottom, glyphs.border_bottom_left, fg, None, none);
+        frame.set(right, bottom, glyphs.border_bottom_right, fg, None, none);
+
+        if let Some(title) = &self.title {
+            let title = truncate(frame, &format!(" {} ", title), area.width.saturating_sub(4));
+            frame.draw_text(
+                area.x + 2,
+                area.y,
+                &title,
+                fg,
+                None,
+                Attributes::from(Attribute::Bold),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+    use crate::glyphs;
+    use crate::widget::render;
+
+    #[test]
+    fn test_panel_draws_border_and_title() {
+        let mut frame = Frame::new(0, 0, 12, 3);
+        frame.set_glyphs(&glyphs::ASCII);
+
+        render(&Panel::new("Bag"), Rect::new(0, 0, 12, 3), &mut frame);
+
+        assert_eq!(
+            frame_to_text(&frame),
+            "+- Bag ----+\n|          |\n+----------+"
+        );
+    }
+
+    #[test]
+    fn test_long_title_is_truncated() {
+        let mut frame = Frame::new(0, 0, 8, 3);
+        frame.set_glyphs(&glyphs::ASCII);
+
+        render(&Panel::new("Inventory"), Rect::new(0, 0, 8, 3), &mut frame);
+
+        assert_eq!(frame_to_text(&frame).lines().next(), Some("+- Inv-+"));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::frame::Frame;
+use crate::layout::{Constraint, Layout, Rect};
+use crate::widget::{truncate, Widget};
+use crossterm::style::{Attribute, Attributes};
+
+/// Rows of text aligned into columns under a bold header.
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// One constraint per column. Every column but the last keeps its final cell blank as a gap.
+    pub widths: Vec<Constraint>,
+    pub selected: Option<usize>,
+}
+
+impl Table {
+    pub fn new(header: Vec<String>, widths: Vec<Constraint>) -> Self {
+        Table {
+            header,
+            rows: Vec::new(),
+            widths,
+            selected: None,
+        }
+    }
+}
+
+impl Widget for Table {
+    fn render(&self, area: Rect, frame: &mut Frame) {
+        let columns = Layout::horizontal(self.widths.clone()).split(area);
+
+        let draw_row = |frame: &mut Frame, y: u16, cells: &[String], attrs: Attributes| {
+            for (i, (column, text)) in columns.iter().zip(cells).enumerate() {
+                let gap = if i + 1 < columns.len() { 1 } else { 0 };
+                let text = truncate(frame, text, column.width.saturating_sub(gap));
+                frame.draw_text(column.x, y, &text, None, None, attrs);
+            }
+        };
+
+        if area.height == 0 {
+            return;
+        }
+
+        draw_row(
+            frame,
+            area.y,
+            &self.header,
+            Attributes::from(Attribute::Bold),
+        );
+
+        let body_height = area.height - 1;
+        let offset = match self.selected {
+            Some(selected) if selected >= body_height as usize => {
+                selected + 1 - body_height as usize
+            }
+            _ => 0,
+        };
+
+        for (row, (index, cells)) in self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(body_height as usize)
+            .enumerate()
+        {
+            let y = area.y + 1 + row as u16;
+
+            if self.selected == Some(index) {
+                let highlight = Attributes::from(Attribute::Reverse);
+                frame.draw_text(
+                    area.x,
+                    y,
+                    &" ".repeat(area.width as usize),
+                    None,
+                    None,
+                    highlight,
+                );
+                draw_row(frame, y, cells, highlight);
+            } else {
+                draw_row(frame, y, cells, Attributes::none());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+    use crate::widget::render;
+
+    #[test]
+    fn test_table_aligns_columns() {
+        let mut frame = Frame::new(0, 0, 16, 3);
+
+        let mut table = Table::new(
+            vec!["Key".into(), "Action".into()],
+            vec![Constraint::Fixed(5), Constraint::Fill],
+        );
+        table.rows.push(vec!["Esc".into(), "Quit".into()]);
+        table.rows.push(vec!["Space".into(), "Wait a turn".into()]);
+        render(&table, Rect::new(0, 0, 16, 3), &mut frame);
+
+        assert_eq!(
+            frame_to_text(&frame),
+            "Key  Action\nEsc  Quit\nSpac Wait a turn"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::frame::Frame;
+use crate::layout::Rect;
+use crate::widget::Widget;
+use crossterm::style::{Attributes, Color};
+use textwrap::wrap;
+
+/// Wrapped, scrollable text. Arrows in the right column show when there is more above or below.
+pub struct TextView {
+    pub text: String,
+    /// The first wrapped line shown.
+    pub scroll: usize,
+    pub fg: Option<Color>,
+}
+
+impl TextView {
+    pub fn new(text: &str) -> Self {
+        TextView {
+            text: text.to_string(),
+            scroll: 0,
+            fg: None,
+        }
+    }
+
+    /// Wraps the text to `width` columns, keeping blank lines between paragraphs.
+    pub fn lines(&self, width: u16) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for paragraph in self.text.split('\n') {
+            if paragraph.trim().is_empty() {
+                lines.push(String::new());
+                continue;
+            }
+
+            lines.extend(
+                wrap(paragraph, width.max(1) as usize)
+                    .iter()
+                    .map(|line| line.to_string()),
+            );
+        }
+
+        lines
+    }
+
+    /// The largest useful `scroll` value for an area, where the last line is at the bottom.
+    pub fn max_scroll(&self, area: Rect) -> usize {
+        let text_width = area.width.saturating_sub(1);
+
+        self.lines(text_width)
+            .len()
+            .saturating_sub(area.height as usize)
+    }
+}
+
+impl Widget for TextView {
+    fn render(&self, area: Rect, frame: &mut Frame) {
+        // The last column is kept free for the scroll indicators
+        let text_width = area.width.saturating_sub(1);
+        let lines = self.lines(text_width);
+        let scroll = self.scroll.min(self.max_scroll(area));
+
+        for (row, line) in lines
+            .iter()
+            .skip(scroll)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            frame.draw_text(
+                area.x,
+                area.y + row as u16,
+                line,
+                self.fg,
+                None,
+                Attributes::none(),
+            );
+        }
+
+        if area.is_empty() {
+            return;
+        }
+
+        let glyphs = frame.glyphs();
+        let indicator_x = area.right() - 1;
+
+        if scroll > 0 {
+            frame.set(
+                indicator_x,
+                area.y,
+                glyphs.scroll_up,
+                None,
+                None,
+                Attributes::none(),
+            );
+        }
+        if scroll + (area.height as usize) < lines.len() {
+            let y = area.bottom() - 1;
+            frame.set(
+                indicator_x,
+                y,
+                glyphs.scroll_down,
+                None,
+                None,
+                Attributes::none(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::headless::frame_to_text;
+    use crate::glyphs;
+    use crate::widget::render;
+
+    #[test]
+    fn test_text_view_scrolls_with_indicators() {
+        let mut frame = Frame::new(0, 0, 8, 2);
+        frame.set_glyphs(&glyphs::ASCII);
+
+        let mut view = TextView::new("one two three four");
+        view.scroll = 1;
+        render(&view, Rect::new(0, 0, 8, 2), &mut frame);
+
+        // Wrapped at 7 columns: "one two", "three", "four"
+        assert_eq!(frame_to_text(&frame), "three  ^\nfour");
+        assert_eq!(view.max_scroll(Rect::new(0, 0, 8, 2)), 1);
+    }
+}